@@ -0,0 +1,179 @@
+//! Derive macro for `loco-factory`.
+//!
+//! `#[derive(Factory)]` reads a `sea-orm` `Model` and expands to the equivalent
+//! `loco_factory::define_factory!` invocation, so the field list doesn't have to be repeated.
+//! Use it through the `loco_factory::Factory` re-export.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Group, Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{Data, DeriveInput, Expr, Fields, Ident, LitStr, Path, parse_macro_input};
+
+/// Generates a factory for a `sea-orm` `Model`.
+///
+/// Only the fields annotated with `#[factory(...)]` are set by the factory; every other
+/// column (e.g. an auto-increment primary key) is left to `ActiveModel::default()`.
+///
+/// Struct attributes:
+/// *   `#[factory(name = "specialty")]` (required): The factory name, used as `$fn_name`.
+/// *   `#[factory(active_model = "ActiveModel")]` (optional): Path to the `ActiveModel`,
+///     `ActiveModel` by default.
+///
+/// Field attributes:
+/// *   `#[factory(default = <expr>)]`: A regular field with its default expression.
+/// *   `#[factory(lazy = <expr>)]`: A lazy field, resolved by calling `<expr>` (a function path
+///     or an inline `|db| async move { ... }` closure) with the connection and awaiting it.
+///
+/// ```rust,ignore
+/// #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Factory)]
+/// #[sea_orm(table_name = "specialties")]
+/// #[factory(name = "specialty")]
+/// pub struct Model {
+///     #[sea_orm(primary_key)]
+///     pub id: i32,
+///     #[factory(default = "Test Specialty".to_string())]
+///     pub name: String,
+///     #[factory(default = true)]
+///     pub is_active: bool,
+/// }
+/// ```
+#[proc_macro_derive(Factory, attributes(factory))]
+pub fn derive_factory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Emits the tokens after `$anchor =>` with every identifier but `$crate` resolved at the
+/// span of `$anchor`.
+///
+/// `define_factory!` uses it for `extends`: the fields of the base factory are replayed from
+/// a macro it generated, so without it they would not be visible to the overrides and
+/// additions written at the extending factory.
+#[doc(hidden)]
+#[proc_macro]
+pub fn respan(input: TokenStream) -> TokenStream {
+    let mut tokens = TokenStream2::from(input).into_iter();
+    let anchor = match tokens.next() {
+        Some(TokenTree::Ident(anchor)) => anchor.span(),
+        _ => {
+            return syn::Error::new(Span::call_site(), "expected the anchor identifier")
+                .into_compile_error()
+                .into();
+        }
+    };
+    respan_tokens(tokens.skip(2).collect(), anchor).into()
+}
+
+fn respan_tokens(tokens: TokenStream2, anchor: Span) -> TokenStream2 {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(mut ident) if ident != "$crate" => {
+                ident.set_span(ident.span().resolved_at(anchor));
+                TokenTree::Ident(ident)
+            }
+            TokenTree::Group(group) => {
+                let mut respanned =
+                    Group::new(group.delimiter(), respan_tokens(group.stream(), anchor));
+                respanned.set_span(group.span());
+                TokenTree::Group(respanned)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let mut name: Option<Ident> = None;
+    let mut active_model: Path = syn::parse_quote!(ActiveModel);
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("factory"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                name = Some(value.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("active_model") {
+                let value: LitStr = meta.value()?.parse()?;
+                active_model = value.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported factory attribute, expected `name` or `active_model`"))
+            }
+        })?;
+    }
+
+    let name = name.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "missing `#[factory(name = \"...\")]` attribute",
+        )
+    })?;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "Factory can only be derived for structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Factory can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut regular = Vec::new();
+    let mut lazy = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let ty = &field.ty;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("factory"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    let default: Expr = meta.value()?.parse()?;
+                    regular.push(quote! { #ident: #ty = #default });
+                    Ok(())
+                } else if meta.path.is_ident("lazy") {
+                    let default: Expr = meta.value()?.parse()?;
+                    lazy.push(quote! { #ident: #ty = #default });
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported factory attribute, expected `default` or `lazy`"))
+                }
+            })?;
+        }
+    }
+
+    let model = &input.ident;
+    let lazy_fields = if lazy.is_empty() {
+        quote! {}
+    } else {
+        quote! { , lazy_fields: { #(#lazy,)* } }
+    };
+
+    Ok(quote! {
+        ::loco_factory::define_factory! {
+            #name => #model {
+                active_model: #active_model,
+                fields: { #(#regular,)* }
+                #lazy_fields
+            }
+        }
+    })
+}
@@ -0,0 +1,84 @@
+//! Test helpers asserting on factory calls.
+
+use sea_orm::{IdenStatic, Iterable, ModelTrait};
+
+/// Awaits a factory call and returns the created model, panicking with the factory name
+/// when the insert fails.
+///
+/// Replaces `.await.unwrap()` in tests, so a failing insert reports which factory broke
+/// instead of a bare `DbErr`:
+///
+/// ```rust,ignore
+/// let specialty = assert_created!(create_specialty(&db));
+/// let doctor = assert_created!(create_doctor_builder().specialty_id(specialty.id).create(&db));
+/// ```
+///
+/// With a summary function (see `summarize_$fn_name`, `summary` feature) and a check, the
+/// created model must also pass the check, otherwise the panic shows the record's summary:
+///
+/// ```rust,ignore
+/// let specialty = assert_created!(create_specialty(&db), summarize_specialty, |s| s.is_active);
+/// // panics with: record created by `create_specialty` failed the check: specialty { id: 1, name: "..." }
+/// ```
+///
+/// Must be used inside an `async` context.
+#[macro_export]
+macro_rules! assert_created {
+    ($factory:ident ( $($args:tt)* ), $summarize:path, $check:expr) => {{
+        let model = $crate::assert_created!($factory($($args)*));
+        if !$crate::assertions::check(&model, $check) {
+            panic!(
+                "record created by `{}` failed the check: {}",
+                stringify!($factory),
+                $summarize(&model)
+            );
+        }
+        model
+    }};
+    ($create:expr, $summarize:path, $check:expr) => {{
+        let model = $crate::assert_created!($create);
+        if !$crate::assertions::check(&model, $check) {
+            panic!(
+                "record created by `{}` failed the check: {}",
+                stringify!($create),
+                $summarize(&model)
+            );
+        }
+        model
+    }};
+    ($factory:ident ( $($args:tt)* )) => {
+        match $factory($($args)*).await {
+            Ok(model) => model,
+            Err(err) => panic!(
+                "factory `{}` failed to create a record: {}",
+                stringify!($factory),
+                err
+            ),
+        }
+    };
+    ($create:expr) => {
+        match $create.await {
+            Ok(model) => model,
+            Err(err) => panic!("`{}` failed to create a record: {}", stringify!($create), err),
+        }
+    };
+}
+
+/// Runs an `assert_created!` check, letting the compiler infer the closure's argument type.
+#[doc(hidden)]
+pub fn check<M, F: FnOnce(&M) -> bool>(model: &M, check: F) -> bool {
+    check(model)
+}
+
+/// Lists the columns whose values differ between `left` and `right`, skipping the columns named
+/// in `ignore`, one `` `column`: left != right `` line each. Used by `assert_$fn_name_eq_ignoring`.
+#[doc(hidden)]
+pub fn model_diff<M: ModelTrait>(left: &M, right: &M, ignore: &[&str]) -> Vec<String> {
+    <<M::Entity as sea_orm::EntityTrait>::Column as Iterable>::iter()
+        .filter(|column| !ignore.contains(&column.as_str()))
+        .filter_map(|column| {
+            let (left, right) = (left.get(column), right.get(column));
+            (left != right).then(|| format!("`{}`: {:?} != {:?}", column.as_str(), left, right))
+        })
+        .collect()
+}
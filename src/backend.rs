@@ -0,0 +1,57 @@
+//! Runtime selection of backend-specific default expressions.
+//!
+//! Factories generated by [`define_factory!`](crate::define_factory) evaluate their defaults
+//! inside [`with_backend`] when inserting, so [`backend_default!`](crate::backend_default) can
+//! pick the expression matching the connection the record goes into.
+
+use std::cell::Cell;
+
+use sea_orm::DatabaseBackend;
+
+thread_local! {
+    static CURRENT_BACKEND: Cell<Option<DatabaseBackend>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with `backend` registered as the backend of the factory being created.
+#[doc(hidden)]
+pub fn with_backend<T>(backend: DatabaseBackend, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_BACKEND.with(|current| current.replace(Some(backend)));
+    let value = f();
+    CURRENT_BACKEND.with(|current| current.set(previous));
+    value
+}
+
+/// Returns the backend of the factory currently evaluating its defaults, if any.
+pub fn current_backend() -> Option<DatabaseBackend> {
+    CURRENT_BACKEND.with(Cell::get)
+}
+
+/// Picks a default expression based on the database backend the factory is inserting into.
+///
+/// The backend is resolved at runtime from `db.get_database_backend()`. Defaults evaluated
+/// without a connection (e.g. by the builder's `new()` or `build()`) use the `sqlite`
+/// expression; the builder's `create` evaluates the defaults not set through a setter again
+/// when the connection's backend differs from the one they were computed for.
+///
+/// ```rust,ignore
+/// define_factory! {
+///     event => events::Model {
+///         active_model: events::ActiveModel,
+///         fields: {
+///             created_at: DateTimeUtc = backend_default!(
+///                 sqlite: Utc::now(),
+///                 postgres: Utc::now().trunc_subsecs(6),
+///             ),
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! backend_default {
+    (sqlite: $sqlite:expr, postgres: $postgres:expr $(,)?) => {
+        match $crate::backend::current_backend() {
+            Some(sea_orm::DatabaseBackend::Postgres) => $postgres,
+            _ => $sqlite,
+        }
+    };
+}
@@ -0,0 +1,201 @@
+//! Deferred inserts of several factories, committed together in one transaction.
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+use futures::future::BoxFuture;
+use sea_orm::{DatabaseConnection, DbErr};
+
+use crate::txn;
+
+/// A builder that can be pushed into a [`FactoryBatch`].
+///
+/// [`define_factory!`](crate::define_factory) implements it for every `Create$fn_name:camel Builder`.
+pub trait BatchInsert: Send + 'static {
+    /// The model inserted by the builder.
+    type Model: Send + 'static;
+
+    /// Types of the `belongs_to` parent models whose foreign key is still left to its default.
+    fn parents(&self) -> Vec<TypeId>;
+
+    /// Sets the foreign key from `parent`, a model of one of the [`parents`](BatchInsert::parents).
+    fn adopt(self, parent: &dyn Any) -> Self;
+
+    /// Creates the record like the builder's `create`.
+    fn create(self, db: &DatabaseConnection) -> BoxFuture<'_, Result<Self::Model, DbErr>>;
+}
+
+type Create = Box<
+    dyn FnOnce(&DatabaseConnection) -> BoxFuture<'_, Result<Box<dyn Any + Send>, DbErr>> + Send,
+>;
+type Adopt = Box<dyn FnOnce(&[&dyn Any]) -> Create + Send>;
+
+struct Step {
+    model: TypeId,
+    parents: Vec<TypeId>,
+    adopt: Adopt,
+}
+
+/// Accumulates builders of any factory and inserts them all at once.
+///
+/// ```rust,ignore
+/// let mut batch = FactoryBatch::new();
+/// let specialty = batch.push(create_specialty_builder());
+/// let doctor = batch.push(create_doctor_builder());
+/// let created = batch.commit(&db).await?;
+/// assert_eq!(created.get(&doctor).specialty_id, created.get(&specialty).id);
+/// ```
+///
+/// [`commit`](FactoryBatch::commit) creates the records in dependency order inside a single
+/// transaction (see [`txn`](crate::txn)), so either all of them are stored or none is, including
+/// the parents created by lazy defaults. A builder whose `belongs_to` key was left to its default
+/// takes it from a parent pushed in the same batch, created first: the last one pushed before it,
+/// else the first one pushed after it. Records are created with the builder's `create`.
+#[derive(Default)]
+#[must_use = "nothing is inserted until the batch is committed"]
+pub struct FactoryBatch {
+    steps: Vec<Step>,
+}
+
+impl FactoryBatch {
+    /// Creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defers the insert of `builder`, returning the handle of its future model.
+    pub fn push<B: BatchInsert>(&mut self, builder: B) -> BatchHandle<B::Model> {
+        let index = self.steps.len();
+        let parents = builder.parents();
+        self.steps.push(Step {
+            model: TypeId::of::<B::Model>(),
+            parents,
+            adopt: Box::new(move |parents| {
+                let builder = parents
+                    .iter()
+                    .fold(builder, |builder, parent| builder.adopt(*parent));
+                Box::new(move |db| {
+                    Box::pin(async move {
+                        let model = builder.create(db).await?;
+                        Ok(Box::new(model) as Box<dyn Any + Send>)
+                    })
+                })
+            }),
+        });
+        BatchHandle {
+            index,
+            model: PhantomData,
+        }
+    }
+
+    /// Number of builders pushed so far.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether no builder was pushed.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Creates every builder, parents first, in one transaction.
+    pub async fn commit(self, db: &DatabaseConnection) -> Result<BatchModels, DbErr> {
+        let (order, sources) = self.plan();
+        let mut adopts: Vec<Option<Adopt>> = self
+            .steps
+            .into_iter()
+            .map(|step| Some(step.adopt))
+            .collect();
+        txn::run(db, || async move {
+            let mut models: Vec<Option<Box<dyn Any + Send>>> =
+                (0..adopts.len()).map(|_| None).collect();
+            for index in order {
+                let adopt = adopts[index].take().expect("each step runs once");
+                let create = {
+                    let parents: Vec<&dyn Any> = sources[index]
+                        .iter()
+                        .filter_map(|&source| models[source].as_deref())
+                        .map(|model| model as &dyn Any)
+                        .collect();
+                    adopt(&parents)
+                };
+                models[index] = Some(create(db).await?);
+            }
+            Ok(BatchModels {
+                models: models.into_iter().flatten().collect(),
+            })
+        })
+        .await
+    }
+
+    // Order of creation and, per step, the steps whose models it takes as parents. A step runs
+    // once its parents did; when a cycle blocks every step, the first one runs with its own defaults
+    fn plan(&self) -> (Vec<usize>, Vec<Vec<usize>>) {
+        let sources: Vec<Vec<usize>> = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                step.parents
+                    .iter()
+                    .filter_map(|parent| {
+                        let before = (0..index).rev().find(|&i| self.steps[i].model == *parent);
+                        before.or_else(|| {
+                            (index + 1..self.steps.len()).find(|&i| self.steps[i].model == *parent)
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut done = vec![false; self.steps.len()];
+        let mut order = Vec::with_capacity(self.steps.len());
+        let mut sources = sources;
+        while order.len() < self.steps.len() {
+            let pending = |i: &usize| !done[*i];
+            let next = (0..self.steps.len())
+                .filter(pending)
+                .find(|&i| sources[i].iter().all(|&source| done[source]))
+                .or_else(|| (0..self.steps.len()).find(pending))
+                .expect("a pending step");
+            sources[next].retain(|&source| done[source]);
+            done[next] = true;
+            order.push(next);
+        }
+        (order, sources)
+    }
+}
+
+/// Position of a builder in a [`FactoryBatch`], used to get its model after the commit.
+#[derive(Debug)]
+pub struct BatchHandle<M> {
+    index: usize,
+    model: PhantomData<fn() -> M>,
+}
+
+impl<M> Clone for BatchHandle<M> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M> Copy for BatchHandle<M> {}
+
+/// The models inserted by [`FactoryBatch::commit`].
+pub struct BatchModels {
+    models: Vec<Box<dyn Any + Send>>,
+}
+
+impl BatchModels {
+    /// Returns the model inserted for `handle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `handle` belongs to another batch.
+    pub fn get<M: 'static>(&self, handle: &BatchHandle<M>) -> &M {
+        self.models
+            .get(handle.index)
+            .and_then(|model| model.downcast_ref())
+            .expect("batch handle from another batch")
+    }
+}
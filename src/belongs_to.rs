@@ -0,0 +1,16 @@
+//! Association of a builder with an already created parent record.
+
+/// Sets the foreign key of a builder from a parent model of type `P`.
+///
+/// [`define_factory!`](crate::define_factory) implements it for the builder of a factory with a
+/// `belongs_to` clause, once per parent, so the parent is passed instead of its raw id:
+///
+/// ```rust,ignore
+/// let specialty = create_specialty(&db).await?;
+/// let doctor = create_doctor_builder().belongs_to(&specialty).create(&db).await?;
+/// let other = create_doctor_for(&db, &specialty).await?;
+/// ```
+pub trait BelongsTo<P> {
+    /// Sets the foreign key referencing `parent`.
+    fn belongs_to(self, parent: &P) -> Self;
+}
@@ -0,0 +1,46 @@
+//! Primary keys of a multi-row insert, used by the generated `create_*_bulk_ids` helpers.
+//!
+//! Without `INSERT ... RETURNING` (SQLite, MySQL) the database only reports the id of the last
+//! inserted row. Auto-increment keys assigned by a single statement are contiguous, so the other
+//! ids are derived from it. Key types are only known once the macro is expanded, so this relies
+//! on the same autoref-based dispatch as [`diff`](crate::diff): integer keys pick
+//! [`ContiguousIds`], every other type falls back to [`UnknownIds`] and reports an error.
+
+use sea_orm::DbErr;
+
+/// The last inserted id and the number of rows inserted by the statement.
+#[doc(hidden)]
+pub struct LastInsertId<T>(pub T, pub usize);
+
+#[doc(hidden)]
+pub trait ContiguousIds<T> {
+    fn ids(&self) -> Result<Vec<T>, DbErr>;
+}
+
+macro_rules! contiguous_ids {
+    ($($int:ty),*) => {
+        $(
+            impl ContiguousIds<$int> for LastInsertId<$int> {
+                fn ids(&self) -> Result<Vec<$int>, DbErr> {
+                    let LastInsertId(last, count) = *self;
+                    Ok((0..count).rev().map(|offset| last - offset as $int).collect())
+                }
+            }
+        )*
+    };
+}
+
+contiguous_ids!(i16, i32, i64, u16, u32, u64);
+
+#[doc(hidden)]
+pub trait UnknownIds<T> {
+    fn ids(&self) -> Result<Vec<T>, DbErr>;
+}
+
+impl<T> UnknownIds<T> for &LastInsertId<T> {
+    fn ids(&self) -> Result<Vec<T>, DbErr> {
+        Err(DbErr::Custom(
+            "bulk ids without RETURNING require an integer primary key".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,89 @@
+//! RAII cleanup of rows created by `create_<name>_guarded` and `create_<name>_guarded_cascade`.
+
+use futures::future::BoxFuture;
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, Iterable, ModelTrait, PrimaryKeyToColumn, QueryFilter, Value,
+};
+
+type Cleanup = Box<dyn FnOnce(DatabaseConnection) -> BoxFuture<'static, Result<(), DbErr>> + Send>;
+
+/// Deletes a created row when cleaned up or dropped.
+///
+/// `Drop` can't be async, so the preferred way is to call [`CleanupGuard::cleanup`] at the
+/// end of the test. A guard dropped without it spawns the delete on the current Tokio
+/// runtime as a best effort: the task may not run if the runtime shuts down first (e.g.
+/// at the end of a `#[tokio::test]`), and its errors are ignored.
+#[must_use = "dropping the guard immediately deletes the row"]
+pub struct CleanupGuard {
+    db: DatabaseConnection,
+    key: Option<Value>,
+    cleanup: Option<Cleanup>,
+}
+
+impl CleanupGuard {
+    /// Creates a guard deleting `model` (by primary key) from `db`.
+    pub fn new<M, A>(db: &DatabaseConnection, model: M) -> Self
+    where
+        M: ModelTrait + IntoActiveModel<A> + Send + 'static,
+        A: ActiveModelTrait<Entity = M::Entity> + ActiveModelBehavior + Send + 'static,
+    {
+        let key = <M::Entity as EntityTrait>::PrimaryKey::iter()
+            .next()
+            .map(|column| model.get(column.into_column()));
+        Self {
+            db: db.clone(),
+            key,
+            cleanup: Some(Box::new(move |db| {
+                Box::pin(async move { model.delete(&db).await.map(|_| ()) })
+            })),
+        }
+    }
+
+    /// Also deletes, before the row itself, the rows whose `column` points at its primary key,
+    /// e.g. `.with_children(doctors::Column::SpecialtyId)` for the doctors of a specialty.
+    ///
+    /// Children are deleted before the row, so foreign keys are never left dangling; with
+    /// several calls, the last registered children go first. Only the first primary key
+    /// column is matched, and grandchildren are not followed.
+    pub fn with_children<C>(mut self, column: C) -> Self
+    where
+        C: ColumnTrait + Send + 'static,
+        C::EntityName: EntityTrait,
+    {
+        let (Some(key), Some(cleanup)) = (self.key.clone(), self.cleanup.take()) else {
+            return self;
+        };
+        self.cleanup = Some(Box::new(move |db| {
+            Box::pin(async move {
+                <C::EntityName as EntityTrait>::delete_many()
+                    .filter(column.eq(key))
+                    .exec(&db)
+                    .await?;
+                cleanup(db).await
+            })
+        }));
+        self
+    }
+
+    /// Deletes the row now, returning the database error if any.
+    pub async fn cleanup(mut self) -> Result<(), DbErr> {
+        match self.cleanup.take() {
+            Some(cleanup) => cleanup(self.db.clone()).await,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take()
+            && let Ok(handle) = tokio::runtime::Handle::try_current()
+        {
+            let future = cleanup(self.db.clone());
+            handle.spawn(async move {
+                let _ = future.await;
+            });
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Timezone-aware helpers for default expressions.
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+
+/// Returns the current instant in the IANA timezone `tz` (e.g. `"America/Sao_Paulo"`).
+///
+/// The result is a `DateTime<FixedOffset>`, i.e. `sea_orm::prelude::DateTimeWithTimeZone`,
+/// carrying the offset in effect in `tz` right now, so it can be used directly as the
+/// default of a `DateTimeWithTimeZone` column:
+///
+/// ```rust,ignore
+/// fields: {
+///     scheduled_at: DateTimeWithTimeZone = now_in("America/Sao_Paulo"),
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics when `tz` is not a known IANA timezone name.
+pub fn now_in(tz: &str) -> DateTime<FixedOffset> {
+    let zone: Tz = tz
+        .parse()
+        .unwrap_or_else(|_| panic!("unknown timezone '{tz}'"));
+    Utc::now().with_timezone(&zone).fixed_offset()
+}
+
+/// A timestamp type that can produce the current instant.
+///
+/// Used by the generated `create_$fn_name_deleted` to fill the `soft_delete` column, so the
+/// macro only depends on the column's declared type and not on a given temporal crate.
+/// Implemented for the `chrono` types `sea-orm` maps timestamp columns to and, with the
+/// `time` feature, for their `time` crate counterparts.
+pub trait Now {
+    /// Returns the current instant.
+    fn now() -> Self;
+}
+
+impl Now for DateTime<Utc> {
+    fn now() -> Self {
+        Utc::now()
+    }
+}
+
+impl Now for DateTime<FixedOffset> {
+    fn now() -> Self {
+        Utc::now().fixed_offset()
+    }
+}
+
+impl Now for NaiveDateTime {
+    fn now() -> Self {
+        Utc::now().naive_utc()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Now for time::OffsetDateTime {
+    fn now() -> Self {
+        time::OffsetDateTime::now_utc()
+    }
+}
+
+#[cfg(feature = "time")]
+impl Now for time::PrimitiveDateTime {
+    fn now() -> Self {
+        let now = time::OffsetDateTime::now_utc();
+        time::PrimitiveDateTime::new(now.date(), now.time())
+    }
+}
@@ -0,0 +1,94 @@
+//! Dependencies between the default expressions of a factory.
+//!
+//! A default can read the fields declared above it (`email: String = format!("{}@x.com",
+//! first_name)`). When a setter changes such a field, the builder evaluates again the defaults
+//! that mention it. Default expressions are only available to the macro as opaque tokens, so
+//! the dependency is found by scanning their `stringify!` output for the field name used as a
+//! variable: string and char literals are skipped, as are names reached through `.` or `::`
+//! and macro names.
+
+/// Whether the stringified default expression `source` reads the variable `name`.
+#[doc(hidden)]
+pub fn mentions(source: &str, name: &str) -> bool {
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if byte == b'"' {
+            i = skip_string(bytes, i + 1);
+        } else if byte == b'r' && matches!(bytes.get(i + 1), Some(b'"' | b'#')) {
+            i = skip_raw_string(bytes, i + 1);
+        } else if byte == b'\'' {
+            i = skip_char(bytes, i + 1);
+        } else if byte == b'_' || byte.is_ascii_alphabetic() {
+            let start = i;
+            while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                i += 1;
+            }
+            if &source[start..i] == name && is_variable(bytes, start, i) {
+                return true;
+            }
+        } else if byte.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                i += 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    false
+}
+
+fn is_variable(bytes: &[u8], start: usize, end: usize) -> bool {
+    let mut before = bytes[..start]
+        .iter()
+        .rev()
+        .filter(|b| !b.is_ascii_whitespace());
+    let after = bytes[end..].iter().find(|b| !b.is_ascii_whitespace());
+    !matches!(
+        (before.next(), before.next(), after),
+        (Some(b'.'), _, _) | (Some(b':'), Some(b':'), _) | (_, _, Some(b'!' | b':'))
+    )
+}
+
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+fn skip_raw_string(bytes: &[u8], mut i: usize) -> usize {
+    let hashes = bytes[i..].iter().take_while(|&&b| b == b'#').count();
+    i += hashes;
+    if bytes.get(i) != Some(&b'"') {
+        return i;
+    }
+    i += 1;
+    while i < bytes.len() {
+        if bytes[i] == b'"' && bytes[i + 1..].iter().take_while(|&&b| b == b'#').count() >= hashes {
+            return i + 1 + hashes;
+        }
+        i += 1;
+    }
+    i
+}
+
+fn skip_char(bytes: &[u8], i: usize) -> usize {
+    match (bytes.get(i), bytes.get(i + 1)) {
+        (Some(b'\\'), _) => bytes
+            .get(i + 2..)
+            .and_then(|rest| rest.iter().position(|&b| b == b'\''))
+            .map_or(bytes.len(), |end| i + 3 + end),
+        (Some(&b), _) if !b.is_ascii() => bytes[i..]
+            .iter()
+            .position(|&b| b == b'\'')
+            .map_or(bytes.len(), |end| i + end + 1),
+        (Some(_), Some(b'\'')) => i + 2,
+        _ => i,
+    }
+}
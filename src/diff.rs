@@ -0,0 +1,31 @@
+//! Value comparison used by the generated `diff_from_defaults` builder method.
+//!
+//! Field types are only known once the macro is expanded, so the comparison relies on
+//! autoref-based dispatch: types implementing `PartialEq` pick [`ComparePartialEq`], every
+//! other type falls back to [`CompareFallback`] and is reported as "unknown" (`None`).
+
+/// Pair of values to compare: the current builder value and the factory default.
+#[doc(hidden)]
+pub struct Compare<'a, T>(pub &'a T, pub &'a T);
+
+#[doc(hidden)]
+pub trait ComparePartialEq {
+    fn differs(&self) -> Option<bool>;
+}
+
+impl<T: PartialEq> ComparePartialEq for Compare<'_, T> {
+    fn differs(&self) -> Option<bool> {
+        Some(self.0 != self.1)
+    }
+}
+
+#[doc(hidden)]
+pub trait CompareFallback {
+    fn differs(&self) -> Option<bool>;
+}
+
+impl<T> CompareFallback for &Compare<'_, T> {
+    fn differs(&self) -> Option<bool> {
+        None
+    }
+}
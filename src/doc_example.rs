@@ -0,0 +1,31 @@
+//! A factory compiled into the library, for the doc tests that need one defined in another
+//! crate than the doctest itself (e.g. to show that `Params` is `#[non_exhaustive]` there).
+
+/// A minimal `notes` entity.
+pub mod notes {
+    use sea_orm::entity::prelude::*;
+
+    #[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+    #[sea_orm(table_name = "notes")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub title: String,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+crate::define_factory! {
+    /// Creates a note
+    note => notes::Model {
+        active_model: notes::ActiveModel,
+        fields: {
+            title: String = "Note".to_string(),
+        },
+        public: true
+    }
+}
@@ -0,0 +1,52 @@
+//! Object-safe access to factories, for runtime registries of seeders.
+
+use std::ops::Deref;
+
+use futures::future::BoxFuture;
+use sea_orm::{DatabaseConnection, DbErr};
+
+/// A factory that can be stored as `Box<dyn DynFactory>`.
+///
+/// [`define_factory!`](crate::define_factory) implements it for the `$fn_name:camel Factory`
+/// marker type of each factory (e.g. `SpecialtyFactory`), so factories of different models
+/// can be kept in a single collection and seeded in a loop:
+///
+/// ```rust,ignore
+/// let seeders: Vec<Box<dyn DynFactory>> = vec![Box::new(SpecialtyFactory), Box::new(DoctorFactory)];
+/// for seeder in &seeders {
+///     seeder.create_default(&db).await?;
+/// }
+/// ```
+pub trait DynFactory: Send + Sync {
+    /// Inserts a record with the factory defaults, like `create_$fn_name`, discarding the model.
+    fn create_default<'a>(&self, db: &'a DatabaseConnection) -> BoxFuture<'a, Result<(), DbErr>>;
+
+    /// The factory name, i.e. `$fn_name`. Defaults to the implementing type's name.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The labels of the factory's `tags` clause. Defaults to none.
+    fn tags(&self) -> &'static [&'static str] {
+        &[]
+    }
+}
+
+/// Returns the names of the factories tagged `tag`, in registry order.
+///
+/// Works on a `Vec<Box<dyn DynFactory>>` as well as on a slice of `&dyn DynFactory`:
+///
+/// ```rust,ignore
+/// let seeders: Vec<Box<dyn DynFactory>> = vec![Box::new(SpecialtyFactory), Box::new(DoctorFactory)];
+/// assert_eq!(factories_with_tag(&seeders, "core"), vec!["specialty"]);
+/// ```
+pub fn factories_with_tag<F>(factories: &[F], tag: &str) -> Vec<&'static str>
+where
+    F: Deref<Target = dyn DynFactory>,
+{
+    factories
+        .iter()
+        .filter(|factory| factory.tags().contains(&tag))
+        .map(|factory| factory.name())
+        .collect()
+}
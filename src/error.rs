@@ -0,0 +1,78 @@
+//! Errors returned by the factory paths that can fail for reasons other than the database.
+
+use std::fmt;
+
+use sea_orm::DbErr;
+
+/// Error of a factory operation.
+///
+/// Generated functions that can only fail while talking to the database keep returning
+/// [`DbErr`]; the ones with richer failure modes (e.g. a builder's `try_build`) return
+/// `FactoryError`. `?` converts a `DbErr` into [`FactoryError::Db`].
+#[derive(Debug)]
+pub enum FactoryError {
+    /// The database rejected the operation.
+    Db(DbErr),
+    /// The values of the record are invalid.
+    Validation(String),
+    /// A lazy field has no value and can't be resolved without a database connection.
+    MissingLazyField(&'static str),
+    /// An override couldn't be applied to the given field.
+    BadOverride { field: String, message: String },
+    /// A row with the same value in a `preflight_unique` field already exists.
+    Duplicate { field: &'static str },
+}
+
+impl fmt::Display for FactoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FactoryError::Db(err) => write!(f, "database error: {err}"),
+            FactoryError::Validation(message) => write!(f, "validation failed: {message}"),
+            FactoryError::MissingLazyField(field) => write!(f, "lazy field '{field}' is not set"),
+            FactoryError::BadOverride { field, message } => {
+                write!(f, "bad override for field '{field}': {message}")
+            }
+            FactoryError::Duplicate { field } => {
+                write!(f, "a row with the same '{field}' already exists")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FactoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FactoryError::Db(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<DbErr> for FactoryError {
+    fn from(err: DbErr) -> Self {
+        FactoryError::Db(err)
+    }
+}
+
+/// A failed `validate` rule, returned by a builder's `validate_then_build`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The field the rule is declared on.
+    pub field: &'static str,
+    /// The message of the rule.
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Runs a `validate` rule, letting the compiler infer the closure's argument type.
+#[doc(hidden)]
+pub fn check<T, F: FnOnce(&T) -> bool>(value: &T, rule: F) -> bool {
+    rule(value)
+}
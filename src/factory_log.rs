@@ -0,0 +1,46 @@
+//! Thread-local record of the rows created by factories (`factory-log` feature).
+//!
+//! Every successful `create_<name>` call, builder `create` and `create_<plural>` appends an
+//! entry, so integration tests can assert which factories ran and in which order, including
+//! the parent rows created by lazy fields.
+//!
+//! The log is per thread: with `#[tokio::test]`'s default current-thread runtime it holds
+//! exactly the rows created by the test, while a multi-thread runtime may record them on
+//! its worker threads instead.
+
+use std::cell::RefCell;
+
+use sea_orm::{EntityName, EntityTrait, ModelTrait, Value};
+
+thread_local! {
+    static FACTORY_LOG: RefCell<Vec<FactoryLogEntry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A row created by a factory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FactoryLogEntry {
+    /// Table the row was inserted into.
+    pub table: String,
+    /// Primary key of the inserted row.
+    pub id: Value,
+}
+
+/// Returns the rows created on this thread since the last [`clear_factory_log`], oldest first.
+pub fn factory_log() -> Vec<FactoryLogEntry> {
+    FACTORY_LOG.with(|log| log.borrow().clone())
+}
+
+/// Empties the log of this thread.
+pub fn clear_factory_log() {
+    FACTORY_LOG.with(|log| log.borrow_mut().clear());
+}
+
+/// Appends `model` to the log, reading its primary key from `pk`.
+#[doc(hidden)]
+pub fn record<M: ModelTrait>(model: &M, pk: <M::Entity as EntityTrait>::Column) {
+    let entry = FactoryLogEntry {
+        table: M::Entity::default().table_name().to_string(),
+        id: model.get(pk),
+    };
+    FACTORY_LOG.with(|log| log.borrow_mut().push(entry));
+}
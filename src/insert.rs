@@ -0,0 +1,43 @@
+//! The default insert routine of the generated factories.
+
+use sea_orm::{
+    ActiveModelBehavior, ActiveModelTrait, ConnectionTrait, DbBackend, DbErr, EntityTrait,
+    IntoActiveModel,
+};
+
+/// Inserts `model` and returns the stored row.
+///
+/// On Postgres the row comes back from `INSERT ... RETURNING` (`exec_with_returning`), so
+/// columns computed by the database are populated without a second query. Other backends use
+/// `ActiveModel::insert`. Both paths run the `ActiveModelBehavior` save hooks.
+pub async fn insert<A, C>(model: A, db: &C) -> Result<<A::Entity as EntityTrait>::Model, DbErr>
+where
+    A: ActiveModelTrait + ActiveModelBehavior + Send,
+    C: ConnectionTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    match db.get_database_backend() {
+        DbBackend::Postgres => {
+            let model = model.before_save(db, true).await?;
+            let inserted = A::Entity::insert(model).exec_with_returning(db).await?;
+            A::after_save(inserted, db, true).await
+        }
+        _ => model.insert(db).await,
+    }
+}
+
+/// Inserts `model` with a plain `INSERT` statement, bypassing the `ActiveModelBehavior` hooks.
+///
+/// Used by factories declared with `run_model_hooks: false`. The stored row is read back with
+/// `RETURNING` where supported, or by primary key otherwise.
+pub async fn insert_without_hooks<A, C>(
+    model: A,
+    db: &C,
+) -> Result<<A::Entity as EntityTrait>::Model, DbErr>
+where
+    A: ActiveModelTrait + ActiveModelBehavior + Send,
+    C: ConnectionTrait,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+{
+    A::Entity::insert(model).exec_with_returning(db).await
+}
@@ -0,0 +1,77 @@
+//! Helpers used by [`define_factory!`](crate::define_factory) to resolve lazy fields.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use sea_orm::{DatabaseConnection, DbErr};
+
+/// Calls a lazy default with the connection: a function path (`get_specialty_id`), a method
+/// path or an inline `|db| async move { ... }` closure.
+///
+/// Like [`call_with_partial`], this lets the compiler infer the closure's argument type.
+#[doc(hidden)]
+pub fn call<'a, F, Fut, T>(db: &'a DatabaseConnection, f: F) -> Fut
+where
+    F: FnOnce(&'a DatabaseConnection) -> Fut,
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    f(db)
+}
+
+/// Calls a `|db, partial| async { ... }` lazy default with the partially-built `ActiveModel`.
+///
+/// Going through this function (instead of calling the closure directly) lets the compiler
+/// infer the closure's argument types from the `ActiveModel` passed in.
+#[doc(hidden)]
+pub fn call_with_partial<'a, A, F, Fut, T>(db: &'a DatabaseConnection, partial: A, f: F) -> Fut
+where
+    F: FnOnce(&'a DatabaseConnection, A) -> Fut,
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    f(db, partial)
+}
+
+/// Evaluates a `lazy_when` condition on the partially-built `ActiveModel`, letting the compiler
+/// infer the closure's argument type.
+#[doc(hidden)]
+pub fn check_when<A, F: FnOnce(&A) -> bool>(partial: &A, condition: F) -> bool {
+    condition(partial)
+}
+
+type Resolver<T> = dyn Fn(DatabaseConnection) -> BoxFuture<'static, Result<T, DbErr>> + Send + Sync;
+
+/// An async closure stored by a builder's `set_<lazy_field>_with` setter.
+///
+/// It receives a clone of the connection passed to `create` (a cheap handle to the same pool),
+/// so `|db| async move { ... }` closures can query it without borrowing issues.
+pub struct LazyWith<T>(Arc<Resolver<T>>);
+
+impl<T> LazyWith<T> {
+    /// Wraps `f`, to be called once per `create`.
+    pub fn new<F, Fut>(f: F) -> Self
+    where
+        F: Fn(DatabaseConnection) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, DbErr>> + Send + 'static,
+    {
+        Self(Arc::new(move |db| Box::pin(f(db))))
+    }
+
+    /// Calls the closure with a clone of `db`.
+    pub async fn resolve(&self, db: &DatabaseConnection) -> Result<T, DbErr> {
+        (self.0)(db.clone()).await
+    }
+}
+
+impl<T> Clone for LazyWith<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for LazyWith<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LazyWith(..)")
+    }
+}
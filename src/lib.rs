@@ -14,6 +14,144 @@
 // [dev-dependencies]
 // tokio = { version = "1", features = ["full", "test-util"] }
 
+// Permite que o código gerado pelo derive use `::loco_factory` dentro deste crate
+extern crate self as loco_factory;
+
+pub use loco_factory_derive::Factory;
+
+pub mod assertions;
+pub mod backend;
+pub mod batch;
+pub mod belongs_to;
+#[doc(hidden)]
+pub mod bulk;
+pub mod cleanup;
+pub mod datetime;
+#[doc(hidden)]
+pub mod defaults;
+#[doc(hidden)]
+pub mod diff;
+#[doc(hidden)]
+pub mod doc_example;
+pub mod dyn_factory;
+pub mod error;
+#[cfg(feature = "factory-log")]
+pub mod factory_log;
+pub mod insert;
+pub mod lazy;
+#[cfg(feature = "log")]
+pub mod or_log;
+pub mod overrides;
+pub mod retry;
+pub mod routing;
+pub mod schema;
+pub mod sequence;
+pub mod transform;
+pub mod txn;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use futures;
+    pub use loco_factory_derive::respan;
+    #[cfg(feature = "loco")]
+    pub use loco_rs;
+    pub use paste;
+    #[cfg(feature = "proptest")]
+    pub use proptest;
+}
+
+/// Emits its input only when the `proptest` feature is enabled.
+#[cfg(feature = "proptest")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_proptest {
+    ($($item:tt)*) => { $($item)* };
+}
+
+/// Emits its input only when the `proptest` feature is enabled.
+#[cfg(not(feature = "proptest"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_proptest {
+    ($($item:tt)*) => {};
+}
+
+pub use batch::{BatchHandle, BatchModels, FactoryBatch};
+pub use belongs_to::BelongsTo;
+pub use cleanup::CleanupGuard;
+pub use dyn_factory::DynFactory;
+pub use error::{FactoryError, FieldError};
+#[cfg(feature = "factory-log")]
+pub use factory_log::{FactoryLogEntry, clear_factory_log, factory_log};
+pub use overrides::{FieldDefaultOverride, with_field_default_override};
+pub use retry::RetryPolicy;
+pub use routing::{DbRouter, PrimaryConn};
+pub use sequence::{SequenceScope, clear_all_sequences};
+
+/// Emits its input only when the `log` feature is enabled.
+#[cfg(feature = "log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_log {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+/// Emits its input only when the `log` feature is enabled.
+#[cfg(not(feature = "log"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_log {
+    ($($tt:tt)*) => {};
+}
+
+/// Emits its input only when the `loco` feature is enabled.
+#[cfg(feature = "loco")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_loco {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+/// Emits its input only when the `loco` feature is enabled.
+#[cfg(not(feature = "loco"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_loco {
+    ($($tt:tt)*) => {};
+}
+
+/// Emits its input only when the `summary` feature is enabled.
+#[cfg(feature = "summary")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_summary {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+/// Emits its input only when the `summary` feature is enabled.
+#[cfg(not(feature = "summary"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_summary {
+    ($($tt:tt)*) => {};
+}
+
+/// Emits its input only when the `factory-log` feature is enabled.
+#[cfg(feature = "factory-log")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_factory_log {
+    ($($tt:tt)*) => { $($tt)* };
+}
+
+/// Emits its input only when the `factory-log` feature is enabled.
+#[cfg(not(feature = "factory-log"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_factory_log {
+    ($($tt:tt)*) => {};
+}
+
 // ============================================
 // MACRO DEFINITION
 // ============================================
@@ -38,10 +176,10 @@
 ///     $fn_name:ident => $model:path {
 ///         active_model: $active_model:path,
 ///         fields: {
-///             $($field:ident: $field_type:ty = $default:expr),* $(,)?
+///             $($(#[doc = $field_doc:literal])* $field:ident: $field_type:ty = $default:expr),* $(,)?
 ///         }
 ///         $(, lazy_fields: { // Optional: For fields whose values depend on async operations or other entities
-///             $($lazy_field:ident: $lazy_type:ty = $lazy_default:expr),* $(,)?
+///             $($(#[doc = $lazy_doc:literal])* $lazy_field:ident: $lazy_type:ty $(= $lazy_default:expr)? $(=> $partial_default:expr)?),* $(,)?
 ///         })?
 ///     }
 /// }
@@ -51,16 +189,81 @@
 ///
 /// *   `$(#[$meta:meta])*`: Optional. Any attributes (e.g., doc comments like `/// Creates a test user`) that will be applied to the generated factory function.
 /// *   `$fn_name:ident`: The base identifier for your factory. This will be used to name the generated functions and builder struct. For example, if `$fn_name` is `user`, it will generate `create_user`, `CreateUserBuilder`, etc.
-/// *   `$model:path`: The full path to your `sea-orm` Model struct (e.g., `users::Model`).
+/// *   `$model:path`: The full path to your `sea-orm` Model struct (e.g., `users::Model`). Entities from another crate work the same, e.g. `my_entities::users::Model`: the generated code reaches `sea-orm` traits through fully qualified paths and needs no import at the call site besides `sea_orm` itself (`paste` is reached through `loco-factory`, so the calling crate doesn't depend on it).
 /// *   `active_model: $active_model:path`: The full path to your `sea-orm` ActiveModel struct (e.g., `users::ActiveModel`).
+///     When the ActiveModel depends on the target backend, list one path per `cfg` instead, e.g. `active_model: { #[cfg(feature = "postgres")] pg::users::ActiveModel, #[cfg(not(feature = "postgres"))] sqlite::users::ActiveModel }`. Each path is only compiled under its own attribute, so exactly one of them must be enabled.
 /// *   `fields`: A comma-separated list of regular fields for your model.
 ///     *   `$field:ident`: The name of the field in your model.
-///     *   `$field_type:ty`: The Rust type of the field.
-///     *   `$default:expr`: An expression that provides the default value for this field. This expression will be used directly to set the `sea_orm::ActiveValue::Set` for the field.
+///     *   `$field_type:ty`: The Rust type of the field. It only needs `Clone` (and `Debug`), so non-`Copy` values such as a JSON column's `serde_json::Value` with a `json!({ "verified": false })` default, or a `rust_decimal::Decimal` money column (`amount: Decimal = Decimal::new(1000, 2)`, with sea-orm's `with-rust_decimal` feature), work as well.
+///     *   `$field_doc:literal` (optional): Doc comments written above the field (`/// Display name`, or `#[doc = "..."]`). They replace the generated "Define o valor de `field`" text on the field's builder setters. Lazy fields accept them as well.
+///     *   `$default:expr`: An expression that provides the default value for this field. This expression will be used directly to set the `sea_orm::ActiveValue::Set` for the field. Use [`backend_default!`] when the default differs between SQLite and Postgres, and [`datetime::now_in`] for a timezone-aware `DateTimeWithTimeZone` default. A value registered with [`with_field_default_override`] for the field's name (and type) takes precedence over the expression.
+///
+///     Defaults are computed top-to-bottom and each one is bound to a local variable named after its field, so a default can read the fields declared above it, e.g. `email: String = format!("{}.{}@x.com", first_name, last_name)`. Referencing a field declared further down is a compile error. A default that reuses a sibling by value must clone it (`first_name.clone()`). On a builder, the setter of a field evaluates again, once, the defaults that read it, so they see the value that was set (`.first_name("Ann")` yields `ann.roe@x.com`); the other defaults keep the values computed by `new()`.
 /// *   `lazy_fields` (optional): A comma-separated list of "lazy" fields. These are fields whose default values are generated by an `async` expression, typically a function that takes a `&sea_orm::DatabaseConnection` and returns a `Result`.
 ///     *   `$lazy_field:ident`: The name of the lazy field in your model.
 ///     *   `$lazy_type:ty`: The Rust type of the lazy field.
-///     *   `$lazy_default:expr`: An `async` expression (e.g., a function call like `get_specialty_id`) that returns `Result<$lazy_type, sea_orm::DbErr>`. This expression will be awaited during model creation.
+///     *   `$lazy_default:expr`: An expression called with the connection and awaited during model creation, returning `Result<$lazy_type, sea_orm::DbErr>`. Either a path to an `async fn(&DatabaseConnection)` like `get_specialty_id` (functions and methods alike) or an inline closure such as `|db| async move { create_specialty(db).await.map(|s| s.id) }`.
+///     *   `$partial_default:expr`: Used instead of `= $lazy_default` when written as `$lazy_field: $lazy_type => |db, partial| async move { ... }`. The closure also receives the partially-built `ActiveModel`, with every regular field already `Set`, so the lazy value can be derived from them (e.g. a `license_number` computed from `last_name`). Lazy fields are resolved top to bottom, in declaration order, and each resolved value is `Set` on the partial model before the next one runs, so a later closure also sees the earlier lazy fields (e.g. `partial.specialty_id`), whether they came from their default or from a builder setter.
+///
+///     Lazy fields are resolved top-to-bottom, after all regular fields.
+/// *   `insert_with` (optional): A function with the signature `async fn(ActiveModel, &C) -> Result<Model, DbErr>` (where `C: sea_orm::ConnectionTrait`) used to persist the model instead of `.insert(db)`. Useful for entities that need a custom insert routine, e.g. to populate encrypted columns. When omitted, [`insert::insert`] is used: `INSERT ... RETURNING` on Postgres, `.insert(db)` elsewhere.
+/// *   `unique` (optional): A list of fields that uniquely identify a row. For each one a `find_$fn_name_by_$field` finder is generated. The field is mapped to the entity's `Column` variant by converting its identifier to camel case (e.g. `uuid` becomes `Column::Uuid`). The lookup goes through that variant, never through a column name string, so fields renamed with `#[sea_orm(column_name = "...")]` are found by their Rust name.
+/// *   `complete` (optional): When `true`, every column of the model must be listed in `fields`/`lazy_fields` (including the primary key) and the generated code omits the `..Default::default()` spread. This makes the macro usable with `ActiveModel`s that don't implement `Default`. Defaults to `false`.
+/// *   `primary_key` (optional): The primary key field of the model and its type, e.g. `primary_key: { id: i32 }`. When omitted, the model is assumed to have an `id: i32` field.
+///     *   `explicit` (optional): Whether the factory sets the primary key itself. By default the key is always emitted as `NotSet`, so the database assigns it (e.g. auto-increment), even when it was listed in `fields` by mistake; setting it on the builder then has no effect. Use `explicit: true` to insert the listed value, e.g. `primary_key: { id: i32, explicit: true }` with `id: i32 = next_id()` in `fields`. The same applies to the implicit `id` key when the clause is omitted. Defaults to `false`.
+///     *   `wrap` (optional): A newtype (e.g. `SpecialtyId`) implementing `From<$pk_type>`. When given, the id returned by the generated helpers is converted into it, so callers get the type-safe id instead of the raw column value.
+/// *   `arbitrary` (optional, `proptest` feature): A proptest strategy for some of the regular fields, e.g. `name: "[a-z]{1,10}"` or `is_active: any::<bool>()`. An `Arbitrary` impl is generated for the factory's `Params` struct where the listed fields are randomized and every other field keeps its factory default. At most 12 fields can be listed. Without the `proptest` feature the clause is ignored.
+/// *   `retry_on_conflict` (optional): How many times `create_$fn_name` retries when the insert fails with a unique constraint violation (see [`retry::is_unique_violation`]). Every attempt re-evaluates the default expressions (and lazy defaults), so sequence or uuid based values get a fresh chance. Other errors are returned immediately. The builder's `create` retries the same way, re-evaluating only the fields not set through a setter; overridden values are kept. Defaults to `0`.
+/// *   `public` (optional): By default the generated helpers are marked `#[allow(dead_code)]`, since a test suite rarely uses all of them. Set `public: true` when the factory is meant for non-test code (e.g. a `seeds` binary): the items are emitted without the `allow`, so the usual dead-code analysis applies to them. The `allow` is selected with a boolean `cfg` literal, hence the crate's `rust-version` of 1.88. Defaults to `false`.
+/// *   `generated` (optional): Columns computed by the database (generated/virtual columns, columns with a DB-side default, auto-increment keys) that the factory must never set. They are explicitly emitted as `ActiveValue::NotSet` in every `ActiveModel` the factory builds, which also lets them count as listed in `complete: true` mode. Listing one of them in `fields` as well is a compile error.
+/// *   `plural` (optional): The plural of `$fn_name`, used to name `create_$plural`, e.g. `plural: specialties`. Defaults to `$fn_name` followed by an `s`.
+/// *   `soft_delete` (optional): The soft-delete column of the model, e.g. `soft_delete: deleted_at`, typed `Option<T>` where `T` implements [`datetime::Now`]: `DateTimeUtc`, `DateTimeWithTimeZone`, `DateTime` (naive) or, with the `time` feature, `time::OffsetDateTime`/`time::PrimitiveDateTime`. The factory always sets it to `None`, so regular records are "alive", and a `create_$fn_name_deleted` variant is generated. Like `generated` columns, it must not be listed in `fields` and counts as listed in `complete: true` mode.
+/// *   `transform` (optional): Closures normalizing the final value of some fields, e.g. `transform: { email: |v| v.to_lowercase() }`. Each closure receives the value about to be written (a default, a builder override, a resolved lazy field or an `update_$fn_name` change) and returns the value stored instead. It runs on every `ActiveModel` the factory builds, after lazy fields are resolved, so `build()` applies it too. Unset fields are left untouched.
+/// *   `returns` and `map` (optional): A different model type returned by the `_mapped` helpers and the closure converting the inserted `$model` into it, e.g. `returns: SpecialtySummary, map: |inserted| SpecialtySummary { id: inserted.id, name: inserted.name }`. Useful when the entity is a view or projection whose shape differs from what callers need. The other helpers keep returning `$model`.
+/// *   `lazy_defaults` (optional): By default the builder evaluates the default expressions once, in `new()`, so every `build()`/`create()` of a stored builder (or of its clones) reuses the same values, e.g. the same `uuid`, unless `create` runs on a connection whose backend differs from the one the defaults were computed for (see [`backend_default!`]). With `lazy_defaults: true` the defaults of the fields not set through a setter are evaluated again by each `build()`/`create()`. Each refresh evaluates the default expressions of those fields only, in declaration order, so they read the values set on the fields above them. `diff_from_defaults` still compares against the values computed by `new()`. Defaults to `false`.
+/// *   `route` (optional): For sharded setups, a closure `|active_model, dbs| &dbs[...]` picking the connection of a [`DbRouter`] a record is inserted into, e.g. by a `region` field. It generates `create_$fn_name_routed(router: &DbRouter)` and the builder's `create_routed(self, router)`; the other helpers keep taking a single connection. The closure sees the `ActiveModel` with every regular field `Set` and the lazy fields still `NotSet`; the lazy defaults are then resolved against the chosen connection.
+/// *   `label_field` (optional, `summary` feature): The field shown next to the primary key by `summarize_$fn_name`, e.g. `label_field: name`. Without it the summary only lists the primary key.
+/// *   `placeholder` (optional): Values standing in for lazy fields in the builder's `build_placeholder()`, e.g. `placeholder: { specialty_id: 0 }`. Unlike `build()`, which panics when a lazy field wasn't set, `build_placeholder()` fills each such field with its placeholder, which suits serialization and snapshot tests that never touch the database. Lazy fields without a placeholder still panic; fields cleared with `unset_*` stay `NotSet`.
+/// *   `tags` (optional): Labels grouping factories for selective seeding, e.g. `tags: ["core", "demo"]`, exposed as `$fn_name:camel Factory::TAGS` and through [`DynFactory::tags`]. Defaults to no tags.
+/// *   `validate` (optional): Rules checked by the builder's `validate_then_build()`, each a closure over a regular field's value and the message reported when it returns `false`, e.g. `validate: { name: |name| !name.is_empty() => "must not be empty" }`. A field may have several rules. Every rule is checked, so all failures come back at once as [`FieldError`]s. The other builder and `create` paths don't run them.
+/// *   `related` (optional): Related entities to load along with a created record, e.g. `related: { specialty: specialties::Entity }`. For each one a `create_$fn_name_with_loaded_$name` helper is generated, returning the created model and the related one loaded through the entity's `Related` impl (`find_also_related`), as endpoint tests asserting nested responses need.
+/// *   `run_model_hooks` (optional): Whether inserts go through `ActiveModelBehavior` (sea-orm's `before_save`/`after_save`). With the default `true` the hooks run, and they always run after the factory is done with the `ActiveModel`: defaults, builder overrides, lazy fields and `transform` are applied first, so `before_save` sees (and may still change) the final values. With `false` a plain `INSERT` statement is executed instead and the hooks are skipped, e.g. to test rows a hook would reject or rewrite. Ignored when `insert_with` is given.
+/// *   `id_newtype` (optional): A name for a newtype the macro defines around the primary key, e.g. `id_newtype: SpecialtyId` defines `pub struct SpecialtyId(pub i32)` with `From` conversions both ways. `get_$fn_name_id` and the other id helpers then return it, so foreign keys can't be mixed up at compile time. Lazy defaults are converted with `Into` into the field type, so `specialty_id: i32 = get_specialty_id` keeps working. Unlike `wrap`, the type is generated; don't combine both.
+/// *   `nullable` (optional): `Option` fields (regular ones) for which a `create_$fn_name_with_null_$field` helper is generated, inserting a record with that field set to `None`, e.g. `nullable: [description]`.
+/// *   `base` (optional): With `base: new`, the columns the factory doesn't set are taken from `ActiveModelBehavior::new()` instead of `ActiveModel::default()`, so values seeded there (e.g. a generated uuid) are kept. Ignored with `complete: true`, which sets every column.
+/// *   `unique_suffix` (optional): A `String` field made unique across a batch, e.g. `unique_suffix: name`. It generates the builder's `create_list_unique_suffix(db, count)`, which appends `-{i}` to the builder's value of that field for each record.
+/// *   `polymorphic` (optional): Pairs of regular fields forming a polymorphic association, as `name: (type_field, id_field)`, e.g. `polymorphic: { commentable: (commentable_type, commentable_id) }`. For each pair the builder gets a `for_$name(type, id)` setter assigning both columns together, so they can't get out of sync.
+/// *   `lazy_when` (optional): Guards on lazy fields, as `field: |partial| condition => sentinel`, e.g. `lazy_when: { specialty_id: |doctor| *doctor.is_active.as_ref() => 0 }`. The lazy default only runs when the condition holds on the partially-built `ActiveModel` (regular fields `Set`, lazy fields not yet resolved); otherwise the field gets the sentinel. A value given through the builder's setters is used either way.
+/// *   `belongs_to` (optional): Foreign keys paired with the factory of the parent they reference, e.g. `belongs_to: { specialty_id: specialty }`. The builder implements [`BelongsTo`] for each parent's model, setting the key from the parent's primary key as that factory declares it (`.belongs_to(&specialty)`), and `create_$fn_name_for(db, &parent)` creates a record tied to a parent created beforehand, instead of passing its raw id.
+/// *   `conn_accessor` (optional): With `conn_accessor: true`, `create_$fn_name` and `create_$fn_name_list` accept any connection wrapper implementing [`PrimaryConn`] (e.g. a struct holding primary and replica connections) and always insert through its `primary()`. A plain `DatabaseConnection` still works, as it implements the trait. The other helpers keep taking a `DatabaseConnection`.
+/// *   `preflight_unique` (optional): Regular fields checked by the builder's `try_create` before inserting, e.g. `preflight_unique: [uuid, email]`. The check reads the values about to be inserted, once lazy fields are resolved and `transform` has run. When a row with the same value already exists, `try_create` returns [`FactoryError::Duplicate`] naming the field instead of the database's unique-violation error. Each field costs one query; `create` doesn't run the checks.
+/// *   `relations` (optional): Foreign keys paired with the factory creating their parent, e.g. `relations: { specialty_id: specialty, clinic_id: clinic }`. It generates `$fn_name:camel Relations`, with one method per parent factory taking a closure that customizes that factory's builder, the builder's `create_with_relations(db, f)` and `create_$fn_name_with_relations(db, f)`: `create_doctor_with_relations(&db, |r| r.specialty(|s| s.name("Cardiology")).clinic(|c| c)).await?`. Each configured parent is created first and its primary key set on the foreign key; the others keep their default. A strategy in parentheses changes how an unconfigured parent is resolved: with `specialty_id: specialty(reuse_existing)`, a foreign key that wasn't set takes the id of an existing specialty through `get_or_create_specialty_id`, which only creates one when the table is empty. The builder also gets `with_$parent(f)`, which stores the customized parent builder and creates it at `create` time; since the parent builder can carry its own `with_$grandparent`, whole chains compose: `create_doctor_builder().with_specialty(|s| s.with_department(|d| d.name("Medicine")))`.
+/// *   `has_many` (optional): Foreign key columns of child entities pointing at this one, e.g. `has_many: [doctors::Column::SpecialtyId]`. It generates `create_$fn_name_guarded_cascade`, whose guard deletes those children before the record itself.
+/// *   `builder_name` (optional): An exact name for the builder, e.g. `builder_name: CreateHTTPRequestBuilder` for an `http_request` factory, whose default `CreateHttpRequestBuilder` comes from paste's camel case. It is declared as a public alias of the default builder, which keeps its name, so every builder method is available under both. Defaults to no alias.
+///
+/// With the `factory-log` feature, every row created through the generated functions is recorded in a thread-local log, read with `factory_log()` and reset with `clear_factory_log()`, so a test can assert which factories ran and in which order.
+///
+/// ### Extending a factory
+///
+/// A factory can reuse another one declared earlier in the same module (or a parent module), listing only the fields it overrides and the ones it adds:
+///
+/// ```rust,ignore
+/// define_factory! {
+///     admin_doctor => doctors::Model {
+///         extends: doctor,
+///         fields: {
+///             email: format!("admin_{}@hospital.com", first_name.to_lowercase()),
+///             is_active: true,
+///         },
+///         public: true
+///     }
+/// }
+/// ```
+///
+/// The overrides are plain expressions (the types come from the base factory's `fields`), while the optional `additions` clause declares new fields like `fields` does, for columns the base leaves unset (e.g. `additions: { description: Option<String> = Some(format!("About {}", name)) }` on a specialty factory without `description`); both can read the base's fields. Overriding a field the base doesn't declare in `fields` is a compile error. The extended factory is expanded as a factory of its own with the base's clauses, so it generates the whole helper set under its own name (`create_admin_doctor`, `CreateAdminDoctorBuilder`, `AdminDoctorParams`, `create_admin_doctor_for`, ...). Names chosen by the base are not inherited: `plural`, `id_newtype` and `builder_name` fall back to their defaults, and `public` is only set by the optional `public` of the extended factory.
+///
+/// As an alternative to listing the fields by hand, the [`Factory`] derive generates the same
+/// invocation from the `Model` struct, reading the defaults from `#[factory(...)]` attributes.
 ///
 /// ## Generated Items
 ///
@@ -73,18 +276,143 @@
 /// 2.  **`struct Create$fn_name:camel Builder`**:
 ///     *   A builder struct (e.g., `CreateUserBuilder`) that allows for more granular control over model creation.
 ///     *   **`fn new() -> Self`**: Creates a new builder instance with all fields initialized to their default values.
-///     *   **`fn $field(mut self, value: $field_type) -> Self`**: A setter method for each non-lazy field, allowing you to override its default value.
-///     *   **`fn $lazy_field(mut self, value: $lazy_type) -> Self`**: A setter method for each lazy field, allowing you to provide a specific value instead of running the `lazy_default` expression.
+///     *   **`fn $field(mut self, value: impl Into<$field_type>) -> Self`**: A setter method for each non-lazy field, allowing you to override its default value. Taking `impl Into` lets call sites pass `"Cardiology"` for a `String`, a bare `String` (or `None`) for an `Option<String>`, or a raw value for a newtype implementing `From`. A `&str` can't go straight into an `Option<String>`, since the standard library only converts `T` into `Option<T>`.
+///     *   **`fn $lazy_field(mut self, value: impl Into<$lazy_type>) -> Self`**: A setter method for each lazy field, allowing you to provide a specific value instead of running the `lazy_default` expression.
+///     *   **`fn set_$field(&mut self, value) -> &mut Self`**: A non-consuming mirror of each setter (regular and lazy fields), convenient when fields are set conditionally, e.g. inside a loop: `if cond { builder.set_is_active(false); }`.
+///     *   **`fn set_$lazy_field_with(self, f) -> Self`**: Sets a lazy field from an async closure called at `create` time with (a clone of) the connection, e.g. `.set_specialty_id_with(|db| async move { newest_specialty_id(&db).await })`, for overrides that need the database themselves. A later plain setter takes precedence. Like an unset lazy field, `build()` can't resolve it.
 ///     *   **`async fn create(self, db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr>`**: Creates and saves the model to the database, using any values explicitly set on the builder, or falling back to defaults/lazy defaults.
+///     *   **`fn in_schema(mut self, schema: impl Into<String>) -> Self`**: Makes `create` insert into the entity's table within the given schema (e.g. `"tenant_42"."doctors"`), for multi-tenant tests. Postgres only: SQLite has no schemas and the insert fails. The custom `insert_with` routine is bypassed when a schema is set.
+///     *   **`fn insert_statement(self, backend: sea_orm::DatabaseBackend) -> sea_orm::Statement`**: The `INSERT` statement `create` would run for the given backend, honoring `in_schema`, without touching a database. Like `build()`, it panics when a lazy field is not set.
+///     *   **`async fn create_mapped(self, db: &sea_orm::DatabaseConnection) -> Result<Returns, sea_orm::DbErr>`**: Same as `create`, converting the inserted model with the `returns` clause (see `create_$fn_name_mapped`).
+///     *   **`async fn create_and_refetch(self, db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr>`**: Same as `create`, then loads the row again by primary key, so values written by the database after the insert (e.g. by a trigger) are returned. Not schema-aware: don't combine with `in_schema`.
+///     *   **`async fn create_list_unique_suffix(self, db: &sea_orm::DatabaseConnection, count: usize) -> Result<Vec<$model>, sea_orm::DbErr>`** (with `unique_suffix`): Creates `count` records from this builder, suffixing the `unique_suffix` field with `-0`, `-1`, ... so a base value like `.name("Ward")` yields `Ward-0`, `Ward-1`, etc.
+///     *   **`fn for_$name(self, type, id) -> Self`** (with `polymorphic`): Sets both columns of a polymorphic association at once, e.g. `.for_commentable("Doctor", 5)`.
+///     *   **`async fn try_create(self, db: &sea_orm::DatabaseConnection) -> Result<$model, FactoryError>`**: Same as `create`, after checking the `preflight_unique` fields; a duplicate value comes back as [`FactoryError::Duplicate`] and a database error as [`FactoryError::Db`].
+///     *   **`fn with_$parent(self, f) -> Self`** (with `relations`): Creates the parent factory's record before the insert and sets its primary key on the foreign key, customizing the parent's builder with `f`. Calling it again customizes the same parent builder further.
+///     *   **`fn with_retry(self, attempts: u32, backoff: Duration) -> Self`**: Makes `create` retry the insert up to `attempts` times, waiting `backoff` before each retry, when it fails with a transient error ([`retry::is_transient`]: a deadlock or serialization failure on Postgres, a busy database on SQLite). The lazy fields are resolved once. `with_retry_policy(RetryPolicy)` takes a [`RetryPolicy`] instead, whose `retry_if` plugs another error classification.
+///     *   **`async fn create_as<T>(self, db, f: impl FnOnce($model) -> T) -> Result<T, sea_orm::DbErr>`**: Same as `create`, converting the inserted model with `f`, e.g. `.create_as(&db, SpecialtyDto::from)` in controller tests returning DTOs.
+///     *   **`async fn create_if(self, db: &sea_orm::DatabaseConnection, cond: bool) -> Result<Option<$model>, sea_orm::DbErr>`**: Same as `create` when `cond` is `true`; otherwise nothing is inserted (lazy defaults included) and `Ok(None)` is returned. Saves wrapping conditional seeding in `if` blocks.
+///     *   **`async fn create_returning_id(self, db: &sea_orm::DatabaseConnection) -> Result<PkType, sea_orm::DbErr>`**: Same as `create`, but returns only the primary key of the inserted row. Handy when wiring foreign keys with a customized parent.
+///     *   **`fn diff_from_defaults(&self) -> Vec<&'static str>`**: Names of the fields whose current value differs from the default computed when the builder was created, plus every lazy field that was explicitly set. Useful to debug which fields a test customized. Fields whose type doesn't implement `PartialEq` can't be compared and are skipped.
+///     *   **`fn unset_$field(mut self) -> Self`**: Emits the field as `ActiveValue::NotSet` (for regular and lazy fields), so the database applies its own column default instead of the factory default. Calling the field's setter afterwards sets it again.
+///     *   **`fn requires_db(&self) -> bool`**: `true` while any lazy field is still unset, i.e. while only `create` (and not `build`) can produce the record. Lets generic code pick the synchronous path when possible.
 ///     *   **`fn build(self) -> $active_model`**: Constructs an `ActiveModel` instance without inserting it into the database. If `lazy_fields` are present and not explicitly set via their setter methods on the builder, calling `build()` will panic, as it cannot resolve asynchronous dependencies without a database connection.
+///     *   **`impl From<Create$fn_name:camel Builder> for $active_model`**: Converts the builder with `build()`, so it can be handed to hand-written sea-orm code expecting an `ActiveModel` via `.into()`. Like `build()`, the conversion panics when a lazy field is not set; use `try_build` to get an error instead.
+///     *   **`fn preview(&self) -> $model`**: Builds the full `Model` from the current builder values without a database, e.g. for view-rendering tests. Columns the factory doesn't set (an auto-increment primary key, `generated` or unset columns) get `Default::default()`, so the id of a previewed record is typically `0`. Like `build()`, it panics when a lazy field is not set, and also when one of those columns has a type without `Default`.
+///     *   **`fn try_build(self) -> Result<$active_model, FactoryError>`**: Same as `build`, but returns [`FactoryError::MissingLazyField`] instead of panicking when a lazy field has no value.
+///     *   **`fn build_changes(self) -> $active_model`**: Builds an `ActiveModel` with only the fields given through a setter `Set`, every other column `NotSet`, e.g. for a partial `update` written by hand. Factory defaults are left out, and lazy fields are `Set` only when given a value.
+///     *   **`fn validate_then_build(self) -> Result<$active_model, Vec<FieldError>>`**: Same as `build`, after checking every `validate` rule against the builder values; returns one [`FieldError`] per failed rule instead of the model.
+///     *   **`fn refresh_defaults(&mut self) -> &mut Self`**: Evaluates every default expression again and overwrites the fields not set through a setter, e.g. to get a fresh `uuid` from a long-lived builder. Overridden fields keep their value. This is what `lazy_defaults: true` does on each `build()`/`create()`.
+///     *   **`fn build_placeholder(self) -> $active_model`**: Same as `build`, but lazy fields without a value get their `placeholder` instead of panicking.
 ///
 /// 3.  **`fn create_$fn_name_builder() -> Create$fn_name:camel Builder`**:
 ///     *   A convenience helper function to create a new builder instance, equivalent to `Create$fn_name:camel Builder::new()`.
 ///     *   Example: `create_user_builder().name("Custom Name".to_string()).create(&db).await?`
 ///
-/// 4.  **`async fn get_$fn_name_id(db: &sea_orm::DatabaseConnection) -> Result<i32, sea_orm::DbErr>`**:
-///     *   An asynchronous helper function that creates a new `$model` using `create_$fn_name` and returns its `id`. This assumes your model has an `id` field of type `i32`.
+/// 4.  **`async fn get_$fn_name_id(db: &sea_orm::DatabaseConnection) -> Result<PkType, sea_orm::DbErr>`**:
+///     *   An asynchronous helper function that creates a new `$model` using `create_$fn_name` and returns its primary key. `PkType` is the `id_newtype`, or the `wrap` type of the `primary_key` clause, or its `$pk_type`, or `i32` when the clause is omitted (in which case the model must have an `id` field).
 ///     *   Example: `let specialty_id = get_specialty_id(&db).await?`
+///     *   **`create_$fn_name_id_and_model`** creates a record and returns `(PkType, $model)`, for a quick `let (id, specialty) = create_specialty_id_and_model(&db).await?`.
+///     *   **`get_or_create_$fn_name_id`** is the "reuse existing" strategy for associations: it returns the id of any row already in the table and only creates one when the table is empty. As a lazy default (`specialty_id: i32 = get_or_create_specialty_id`) or through the `reuse_existing` strategy of `relations`, every child then shares a single parent, while `get_$fn_name_id` always creates a new one.
+///
+/// 5.  **`async fn find_$fn_name_by_$field(db: &sea_orm::DatabaseConnection, value: impl Into<sea_orm::Value>) -> Result<Option<$model>, sea_orm::DbErr>`** (only for fields listed in `unique`):
+///     *   Looks up a single row by the given unique field.
+///     *   Example: `let found = find_specialty_by_uuid(&db, specialty.uuid).await?`
+///     *   **`get_or_create_$fn_name_by_$field(db, key)`** returns `($model, bool)`: the row found by `key`, or a new one created with the factory defaults and `key` as that field's value. The flag is `true` only when the row was created, for idempotent seeding.
+///
+/// 6.  **`fn create_$fn_name_stream(db: &sea_orm::DatabaseConnection, count: usize) -> impl Stream<Item = Result<$model, sea_orm::DbErr>>`**:
+///     *   A lazy stream that inserts up to `count` records, one each time it is polled. Nothing is inserted until the stream is consumed, so it can be combined with `take`, throttling or other stream adapters in load tests.
+///     *   Example: `create_specialty_stream(&db, 1_000).take(100).collect::<Vec<_>>().await`
+///
+/// 7.  **`async fn update_$fn_name(db: &sea_orm::DatabaseConnection, id, f: impl FnOnce(Builder) -> Builder) -> Result<$model, sea_orm::DbErr>`**:
+///     *   Loads the row with the given primary key, applies only the fields set on the builder by `f` and saves it. Fields left untouched keep their stored values. The builder handed to `f` holds the row's values, so no default expression is evaluated (sequences don't advance). Returns `DbErr::RecordNotFound` when no row matches.
+///     *   Example: `update_specialty(&db, specialty.id, |b| b.name("Renamed".to_string())).await?`
+///
+/// 8.  **`struct $fn_name:camel Params`** and **`async fn create_$fn_name_from(db: &sea_orm::DatabaseConnection, params) -> Result<$model, sea_orm::DbErr>`**:
+///     *   A plain struct with a public field per regular field (and an `Option` per lazy field), convertible to and from the builder. `create_$fn_name_from` inserts a row using every value of the params (`create_$fn_name_from_params` is kept as an alias).
+///     *   The struct is `#[non_exhaustive]`, so adding a field to the factory doesn't break code building params in other crates (e.g. with `public: true` factories shared by a test-support crate). There, params can't be written as a struct literal, not even with `..Default::default()`: start from `Params::default()` (or `Params::from(builder)`) and assign the fields to change. Inside the crate invoking the macro, the attribute has no effect.
+///
+///         ```rust,compile_fail,E0639
+///         // `NoteParams` comes from a `public: true` factory of another crate
+///         let params = loco_factory::doc_example::NoteParams {
+///             title: "Outside".to_string(),
+///             ..Default::default()
+///         };
+///         ```
+///
+///         ```rust
+///         let mut params = loco_factory::doc_example::NoteParams::default();
+///         params.title = "Outside".to_string();
+///         ```
+///     *   `Params` implements `Default` with the factory defaults, and the builder starts from it, so tests can use struct-update syntax: `create_specialty_from(&db, SpecialtyParams { name: "X".into(), ..Default::default() })`.
+///     *   The builder's `spread(self, params: &Params)` layers a params struct onto an existing builder: only the fields set on the params are copied (those assigned a value other than the one they started from, every field set through a setter when the params come from a builder, and every lazy field that is `Some`), the others keep what the builder already has. Shared params can thus be spread after some manual setters without resetting them: `create_specialty_builder().name("Manual").spread(&shared)`. Fields whose type lacks `PartialEq` are compared through their `Debug` output. The values a params struct started from are recorded per thread by the last `Params::default()` or `Params::from(builder)` of the factory, so spread a params struct before building another one from the same factory; without a record every field is copied.
+///     *   The builder's `into_params(self)` is the reverse conversion, spelled as a method: a snapshot of what the builder would insert, for debugging or replaying it later with `create_$fn_name_from`. Lazy fields only hold a value when one was set on the builder; those resolved from the database at `create` time are `None`. `params -> builder -> into_params` gives back the same params.
+///     *   With the `proptest` feature and an `arbitrary` clause, `Params` implements `proptest::arbitrary::Arbitrary`, so `any::<SpecialtyParams>()` can drive property-based tests.
+///
+/// 9.  **`async fn seed_$fn_name_fixtures(db: &sea_orm::DatabaseConnection, fixtures: &[fn(Builder) -> Builder]) -> Result<Vec<$model>, sea_orm::DbErr>`**:
+///     *   Inserts one row per fixture, each one customizing a fresh builder, and returns them in order. Keeps canonical reference rows in a single table of non-capturing closures.
+///     *   Example: `seed_specialty_fixtures(&db, &[|b| b.name("Cardiology".into()), |b| b.name("Neurology".into())]).await?`
+///
+/// 10. **`async fn create_$plural(db: &sea_orm::DatabaseConnection, builders: Vec<Builder>) -> Result<Vec<$model>, sea_orm::DbErr>`**:
+///     *   Inserts one row per builder inside a single transaction and returns them in order. If any insert fails the transaction is rolled back and none of the rows is kept.
+///     *   Each builder is created inside the transaction (see [`txn`]), so parent rows created by lazy defaults, `with_$parent` or `reuse_existing` are rolled back with it.
+///     *   Example: `create_specialties(&db, vec![create_specialty_builder(), create_specialty_builder().name("X".into())]).await?`
+///
+/// 11. **`async fn create_$fn_name_deleted(db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr>`** (only with `soft_delete`):
+///     *   Same as `create_$fn_name`, but the soft-delete column is set to the current time, producing an already deleted record.
+///     *   Example: `let removed = create_post_deleted(&db).await?`
+///
+/// 12. **`async fn create_$fn_name_list(db: &sea_orm::DatabaseConnection, count: usize) -> Result<Vec<$model>, sea_orm::DbErr>`**, **`create_$fn_name_list_with(db, count, f)`** and **`create_$fn_name_list_from(db, start, count, f)`**:
+///     *   `create_$fn_name_list` inserts `count` records with the factory defaults, one after the other.
+///     *   `create_$fn_name_list_ids(db, count)` does the same but returns only the primary keys (or `id_newtype` values) as a `Vec`, e.g. to fill a link table.
+///     *   `create_$fn_name_bulk_ids(db, count)` inserts the `count` records with a single multi-row `INSERT` and returns their primary keys. On Postgres the keys come from `RETURNING`; SQLite (and MySQL) only report the last inserted id, so the others are derived from it, which requires an auto-increment integer key and no concurrent inserts into the table (any other key type returns a `DbErr::Custom`). The records skip the retries, model hooks and custom `insert_with` of `create_$fn_name`.
+///     *   `create_$fn_name_list_with` customizes each record with `f: impl Fn(usize, Builder) -> Builder`, called with the index of the record (`0..count`) and a fresh builder.
+///     *   `create_$fn_name_list_from` does the same with indexes `start..start + count`, so a later batch continues the numbering of a previous one.
+///     *   `create_$fn_name_map_by(db, keys, f)` creates one record per key, customized by `f: impl Fn(&K, Builder) -> Builder`, and returns a `HashMap` from each key to its model, e.g. reference data looked up by name afterwards: `create_specialty_map_by(&db, &["Cardiology", "Neurology"], |name, b| b.name(*name))`. A repeated key creates another record and keeps the last one in the map.
+///     *   Example: `create_specialty_list_from(&db, 3, 3, |i, b| b.name(format!("item-{i}"))).await?`
+///     *   `build_$fn_name_list_with(count, f)` is the in-memory counterpart of `create_$fn_name_list_with`: it returns the `build()` of each customized builder as `Vec<$active_model>`, without a database. Every item starts from a fresh builder, so defaults like uuids are regenerated per item; lazy fields must be set by `f`.
+///     *   `create_$fn_name_list_sharing_parents(db, count)` resolves the lazy fields only for the first record and reuses their values for the rest, e.g. ten doctors sharing a single specialty created by `get_specialty_id`.
+///     *   `create_$fn_name_list_concurrent(db, count, concurrency)` inserts `count` default records running up to `concurrency` inserts at a time over the connection pool (at least one). The records come back in completion order, not creation order. Every insert is awaited, even after a failure; if any failed, the first error (in completion order) is returned.
+///
+/// 13. **`struct $fn_name:camel Factory`**:
+///     *   A unit marker type implementing [`DynFactory`], whose `create_default` runs `create_$fn_name`. Lets factories of different models live in a `Vec<Box<dyn DynFactory>>`, e.g. a registry of seeders.
+///     *   Its `TAGS` constant (also returned by `DynFactory::tags`) holds the `tags` clause, so a registry can be filtered with [`factories_with_tag`](dyn_factory::factories_with_tag).
+///
+/// 14. **`async fn create_$fn_name_mapped(db: &sea_orm::DatabaseConnection) -> Result<Returns, sea_orm::DbErr>`**:
+///     *   Same as `create_$fn_name`, but returns the inserted model converted by the `map` closure of the `returns` clause. Without the clause `Returns` is `$model` and the model is returned as is.
+///
+/// 15. **`async fn create_$fn_name_guarded(db: &sea_orm::DatabaseConnection) -> Result<($model, CleanupGuard), sea_orm::DbErr>`**:
+///     *   Same as `create_$fn_name`, plus a [`CleanupGuard`] deleting the row, for tests sharing a database. Call `guard.cleanup().await` to delete it deterministically; dropping the guard only spawns a best-effort delete. Parent rows created by lazy fields are not deleted.
+///     *   With `has_many`, `create_$fn_name_guarded_cascade` returns a guard that first deletes the child rows pointing at the record (e.g. the doctors of a specialty, whoever created them), then the record, in an order the foreign keys accept. Only direct children are deleted.
+///     *   Example: `let (specialty, guard) = create_specialty_guarded(&db).await?;`
+///
+/// 16. **`fn $fn_name_column_of(field: &str) -> Option<Column>`**:
+///     *   Maps the name of a field known to the factory (regular, lazy, `generated` or `soft_delete`) to the entity's `Column` variant, converting the identifier to camel case like the `unique` finders do. Returns `None` for any other name.
+///     *   Example: `let column = specialty_column_of("name"); // Some(specialties::Column::Name)`
+///
+/// 17. **`fn summarize_$fn_name(model: &$model) -> String`** (`summary` feature):
+///     *   A one-line summary of a created record listing its primary key and the `label_field`, e.g. `specialty { id: 1, name: "Cardiology" }`. Meant for test output, see [`assert_created!`].
+///
+/// 18. **`async fn assert_$fn_name_count(db: &sea_orm::DatabaseConnection, expected: u64)`**:
+///     *   Counts the rows of the model's table and panics when the count differs from `expected`, with a message naming the table and both numbers, e.g. ``table `doctors` has 3 rows, expected 2``.
+///     *   **`fn assert_$fn_name_eq_ignoring(left: &$model, right: &$model, ignore: &[&str])`** compares two models column by column, except the columns named in `ignore` (e.g. `&["id", "uuid"]`), and panics listing every differing column with both values. Columns are named as in the table, which is the field name unless renamed with `column_name`.
+///
+/// 19. **`async fn create_$fn_name_ctx(ctx: &loco_rs::app::AppContext) -> Result<$model, sea_orm::DbErr>`** (`loco` feature):
+///     *   Same as `create_$fn_name`, taking the connection from a Loco `AppContext` (`ctx.db`). The feature brings in `loco-rs` (with its `with-db` feature), which the generated code reaches through `loco-factory`.
+///
+/// 20. **`async fn create_$fn_name_or_log(db: &sea_orm::DatabaseConnection) -> Option<$model>`** (`log` feature):
+///     *   Same as `create_$fn_name`, but a failure is logged at `error` level through the `log` crate (target `loco_factory`) and `None` is returned, for callers like seed binaries that log and go on.
+///
+/// 21. **`fn $fn_name_sequence_next() -> u64`**, **`fn reset_$fn_name_sequence()`** and **`fn $fn_name_sequence_scope() -> SequenceScope`**:
+///     *   A counter per factory (and per thread) for numbered defaults, e.g. `name: String = format!("Specialty {}", specialty_sequence_next())`. It starts at 1 and only moves when `$fn_name_sequence_next` is called.
+///     *   `$fn_name_sequence_scope` returns a [`SequenceScope`] guard resetting the counter on creation and again when dropped: `let _scope = specialty_sequence_scope();` at the top of a test gives deterministic numbering.
+///     *   [`clear_all_sequences`] resets the sequences of every factory at once, e.g. in a shared test setup.
+///
+/// 22. **`impl BatchInsert for Create$fn_name:camel Builder`**:
+///     *   Lets builders of any factory be pushed into a [`FactoryBatch`], which creates them all in a single transaction, parents first, filling the `belongs_to` keys left to their default from the parents pushed in the batch: `let doctor = batch.push(create_doctor_builder());`, then `batch.commit(&db).await?.get(&doctor)`.
+///
+/// 23. **`trait $fn_name:camel FactoryExt`**, implemented for `sea_orm::DatabaseConnection`:
+///     *   Method-style calls on the connection: `db.create_specialty().await?` and `db.create_specialty_list(3).await?` do the same as the free functions, which remain available. The trait must be in scope (`use crate::factories::SpecialtyFactoryExt;`) where it is called.
 ///
 /// ## Examples
 ///
@@ -276,38 +604,419 @@
 /// ```
 #[macro_export]
 macro_rules! define_factory {
+    // Persists the ActiveModel, using the custom `insert_with` routine when given
+    (@insert $active:expr, $db:expr, [false]) => {
+        $crate::insert::insert_without_hooks($active, $db).await
+    };
+    (@insert $active:expr, $db:expr, [$($run_hooks:literal)?]) => {
+        $crate::insert::insert($active, $db).await
+    };
+    (@insert $active:expr, $db:expr, [$($run_hooks:literal)?] $insert_fn:path) => {
+        $insert_fn($active, $db).await
+    };
+
+    // Builds the ActiveModel literal, filling the remaining columns from `Default` (or
+    // `ActiveModelBehavior::new` with `base: new`) unless `complete: true`
+    (@active_model $active:ident { $($body:tt)* } [true] [$($base:ident)?]) => {
+        $active { $($body)* }
+    };
+    (@active_model $active:ident { $($body:tt)* } [$($complete:literal)?] [new]) => {
+        $active { $($body)* ..<$active as sea_orm::ActiveModelBehavior>::new() }
+    };
+    (@active_model $active:ident { $($body:tt)* } [$($complete:literal)?] []) => {
+        $active { $($body)* ..Default::default() }
+    };
+
+    // Resolves the default value of a lazy field
+    (@lazy_value $db:ident, $partial:ident, [$lazy_default:expr] []) => {
+        ::core::convert::Into::into($crate::lazy::call($db, $lazy_default).await?)
+    };
+    (@lazy_value $db:ident, $partial:ident, [] [$partial_default:expr]) => {
+        $crate::lazy::call_with_partial($db, $partial.clone(), $partial_default).await?
+    };
+
+    // Wraps a builder value in `Set`, or `NotSet` when the field was unset on the builder
+    (@value $unset:expr, $field:ident, $value:expr) => {
+        if $unset.contains(stringify!($field)) {
+            sea_orm::ActiveValue::NotSet
+        } else {
+            sea_orm::ActiveValue::Set($value)
+        }
+    };
+
+    // Applies the `transform` closures to the values `Set` on the ActiveModel
+    (@transform $active:ident $(, $field:ident: $transform:expr)*) => {
+        $(
+            if let sea_orm::ActiveValue::Set(value) =
+                ::core::mem::replace(&mut $active.$field, sea_orm::ActiveValue::NotSet)
+            {
+                $active.$field = sea_orm::ActiveValue::Set($crate::transform::apply(value, $transform));
+            }
+        )*
+    };
+
+    // Leaves the primary key NotSet, so the database assigns it, unless `explicit: true`
+    (@primary_key_not_set $active:ident, [true] $($pk_field:ident)?) => {};
+    (@primary_key_not_set $active:ident, [$($explicit:literal)?] $pk_field:ident) => {
+        $active.$pk_field = sea_orm::ActiveValue::NotSet;
+    };
+    (@primary_key_not_set $active:ident, [$($explicit:literal)?]) => {
+        $active.id = sea_orm::ActiveValue::NotSet;
+    };
+
+    // Model returned by the `_mapped` helpers and the conversion into it (identity by default)
+    (@returns_type $model:path, $returns:ty) => { $returns };
+    (@returns_type $model:path) => { $model };
+    (@returns_map $inserted:expr, $map:expr) => { $crate::transform::map($inserted, $map) };
+    (@returns_map $inserted:expr) => { $inserted };
+
+    // Whether the builder re-evaluates the defaults at build()/create() time
+    (@lazy_defaults $lazy_defaults:literal) => { $lazy_defaults };
+    (@lazy_defaults) => { false };
+
+    // Number of times a unique violation is retried by the factory function
+    (@retries $retries:literal) => { $retries };
+    (@retries) => { 0 };
+
+    // Strategy of a `relations` entry whose parent wasn't configured nor its key set: `reuse_existing`
+    // takes the id of an existing parent, without a strategy the key keeps its default
+    (@relation_strategy $builder:ident, $db:ident, $fk:ident, $get_or_create:ident, reuse_existing) => {
+        if !$builder.__overridden.contains(stringify!($fk)) && !$builder.__unset.contains(stringify!($fk)) {
+            $builder = $builder.$fk($get_or_create($db).await?);
+        }
+    };
+    (@relation_strategy $builder:ident, $db:ident, $fk:ident, $get_or_create:ident, $strategy:ident) => {
+        compile_error!(concat!("unknown relation strategy `", stringify!($strategy), "`, expected `reuse_existing`"))
+    };
+    (@relation_strategy $builder:ident, $db:ident, $fk:ident, $get_or_create:ident) => {};
+
+    // Connection taken by `create_*` and `create_*_list`: any `PrimaryConn` with `conn_accessor: true`
+    (@conn_type [true]) => { impl $crate::PrimaryConn };
+    (@conn_type [$($conn_accessor:literal)?]) => { sea_orm::DatabaseConnection };
+
+    // Id type and value returned by the `get_*_id` helpers: the `id_newtype`, else the primary key
+    (@id_type [$id_newtype:ident] $($rest:tt)*) => { $id_newtype };
+    (@id_type [] $($rest:tt)*) => { $crate::define_factory!(@pk_type $($rest)*) };
+    (@id_value [$id_newtype:ident] $model:expr $(, $pk_field:ident $(, $pk_wrap:path)?)?) => {
+        <$id_newtype as ::core::convert::From<_>>::from($crate::define_factory!(@pk_value $model $(, $pk_field)?))
+    };
+    (@id_value [] $model:expr $(, $pk_field:ident $(, $pk_wrap:path)?)?) => {
+        $crate::define_factory!(@pk_value $model $(, $pk_field $(, $pk_wrap)?)?)
+    };
+
+    (@pk_type $pk_type:ty, $pk_wrap:path) => { $pk_wrap };
+    (@pk_type $pk_type:ty) => { $pk_type };
+    (@pk_type) => { i32 };
+
+    // Reads the primary key out of a model, converting it into the `wrap` newtype when given
+    (@pk_value $model:expr, $pk_field:ident, $pk_wrap:path) => {
+        <$pk_wrap as ::core::convert::From<_>>::from($model.$pk_field)
+    };
+    (@pk_value $model:expr, $pk_field:ident) => { $model.$pk_field };
+    (@pk_value $model:expr) => { $model.id };
+
+    // Doc text of a builder setter: the field's own doc comments, or the generated text
+    (@setter_doc $field:ident, $text:literal $(, $suffix:literal)?;) => {
+        concat!($text, " `", stringify!($field), "`" $(, $suffix)?)
+    };
+    (@setter_doc $field:ident, $text:literal $(, $suffix:literal)?; $($doc:literal)+) => {
+        concat!($($doc, "\n"),+)
+    };
+
+    (@pk_name $pk_field:ident) => { stringify!($pk_field) };
+    (@pk_name) => { "id" };
+
+    // Primary key column of a model, used by the factory log
+    (@pk_column $model:path, $column:ident) => {
+        <<$model as sea_orm::ModelTrait>::Entity as sea_orm::EntityTrait>::Column::$column
+    };
+    (@pk_column $model:path) => {
+        <<$model as sea_orm::ModelTrait>::Entity as sea_orm::EntityTrait>::Column::Id
+    };
+
+    // Inserts every builder in one transaction, named after the `plural` clause or `<fn_name>s`
+    (@create_all [$($public:literal)?] [$plural:ident] $default:ident, $builder:ident, $model:path) => {
+        $crate::define_factory!(@create_all_fn [$($public)?] $plural, $builder, $model);
+    };
+    (@create_all [$($public:literal)?] [] $default:ident, $builder:ident, $model:path) => {
+        $crate::define_factory!(@create_all_fn [$($public)?] $default, $builder, $model);
+    };
+    (@create_all_fn [$($public:literal)?] $name:ident, $builder:ident, $model:path) => {
+        $crate::__private::paste::paste! {
+            /// Cria um registro por builder numa única transação
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $name>](
+                db: &sea_orm::DatabaseConnection,
+                builders: Vec<$builder>,
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                // Os pais criados pelos campos lazy também entram na transação
+                $crate::txn::run(db, || async move {
+                    let mut created = Vec::with_capacity(builders.len());
+                    for builder in builders {
+                        created.push(builder.create(db).await?);
+                    }
+                    Ok(created)
+                })
+                .await
+            }
+        }
+    };
+
+    // Factory extending another one: the base replays its definition through `__<base>_extend!`
+    (
+        $(#[$meta:meta])*
+        $fn_name:ident => $model:path {
+            extends: $base:ident,
+            fields: {
+                $($field:ident: $value:expr),* $(,)?
+            }
+            $(, additions: {
+                $($(#[doc = $addition_doc:literal])* $addition:ident: $addition_type:ty = $addition_default:expr),* $(,)?
+            })?
+            $(, public: $public:literal)?
+            $(,)?
+        }
+    ) => {
+        $crate::__private::paste::paste! {
+            [<__ $base _extend>]!({
+                $(#[$meta])*
+                $fn_name => $model,
+                fields: { $($field: $value),* },
+                additions: { $($($(#[doc = $addition_doc])* $addition: $addition_type = $addition_default),*)? },
+                public: [$($public)?]
+            });
+        }
+    };
+    (@extend {
+        $(#[$meta:meta])*
+        $fn_name:ident => $model:path,
+        fields: { $($override_field:ident: $override_value:expr),* },
+        additions: { $($(#[doc = $addition_doc:literal])* $addition:ident: $addition_type:ty = $addition_default:expr),* },
+        public: [$($public:literal)?]
+    } {
+        active_model: $active_model:path,
+        fields: {
+            $($(#[doc = $field_doc:literal])* $field:ident: $field_type:ty = $default:expr),*
+        }
+        [$($before:tt)*]
+        [$($after:tt)*]
+    }) => {
+        $crate::__private::paste::paste! {
+            // Default de cada campo da base: o valor sobrescrito por esta factory, se houver
+            #[allow(unused_macros)]
+            macro_rules! [<__ $fn_name _default>] {
+                $(($override_field, $fallback:expr) => { $override_value };)*
+                ($other:ident, $fallback:expr) => { $fallback };
+            }
+
+            // Os campos da base vêm de `__<base>_extend!`: o respan os torna visíveis às sobrescritas
+            $crate::__private::respan! {
+                $fn_name => $crate::define_factory! {
+                    $(#[$meta])*
+                    $fn_name => $model {
+                        active_model: $active_model,
+                        fields: {
+                            $($(#[doc = $field_doc])* $field: $field_type = [<__ $fn_name _default>]!($field, $default),)*
+                            $($(#[doc = $addition_doc])* $addition: $addition_type = $addition_default,)*
+                        }
+                        $($before)*
+                        $(, public: $public)?
+                        $($after)*
+                    }
+                }
+            }
+
+            // Rejeita sobrescritas de campos que não estão entre os `fields` da base
+            #[allow(dead_code)]
+            struct [<__ $fn_name:camel BaseFields>] {
+                $($field: (),)*
+            }
+            const _: fn([<__ $fn_name:camel BaseFields>]) = |fields| {
+                $(let () = fields.$override_field;)*
+                let _ = fields;
+            };
+        }
+    };
+
+    // ActiveModel picked by cfg: aliases the enabled path and expands the regular factory with it
+    (
+        $(#[$meta:meta])*
+        $fn_name:ident => $model:path {
+            active_model: { $(#[$am_cfg:meta] $am_path:path),+ $(,)? },
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::__private::paste::paste! {
+            $(
+                #[$am_cfg]
+                #[doc(hidden)]
+                type [<__ $fn_name:camel ActiveModel>] = $am_path;
+            )+
+
+            $crate::define_factory! {
+                $(#[$meta])*
+                $fn_name => $model {
+                    active_model: [<__ $fn_name:camel ActiveModel>],
+                    $($rest)*
+                }
+            }
+        }
+    };
+
     (
         $(#[$meta:meta])*
         $fn_name:ident => $model:path {
             active_model: $active_model:path,
             fields: {
-                $($field:ident: $field_type:ty = $default:expr),* $(,)?
+                $($(#[doc = $field_doc:literal])* $field:ident: $field_type:ty = $default:expr),* $(,)?
             }
             $(, lazy_fields: {
-                $($lazy_field:ident: $lazy_type:ty = $lazy_default:expr),* $(,)?
+                $($(#[doc = $lazy_doc:literal])* $lazy_field:ident: $lazy_type:ty $(= $lazy_default:expr)? $(=> $partial_default:expr)?),* $(,)?
+            })?
+            $(, insert_with: $insert_fn:path)?
+            $(, unique: [$($unique_field:ident),* $(,)?])?
+            $(, complete: $complete:literal)?
+            $(, primary_key: {
+                $pk_field:ident: $pk_type:ty $(, wrap: $pk_wrap:path)? $(, explicit: $pk_explicit:literal)? $(,)?
             })?
+            $(, arbitrary: {
+                $($arb_field:ident: $arb_strategy:expr),* $(,)?
+            })?
+            $(, retry_on_conflict: $retries:literal)?
+            $(, public: $public:literal)?
+            $(, generated: { $($generated_field:ident),* $(,)? })?
+            $(, plural: $plural:ident)?
+            $(, soft_delete: $soft_delete_field:ident)?
+            $(, transform: { $($transform_field:ident: $transform_fn:expr),* $(,)? })?
+            $(, returns: $returns:ty, map: $returns_map:expr)?
+            $(, lazy_defaults: $lazy_defaults:literal)?
+            $(, route: $route:expr)?
+            $(, label_field: $label_field:ident)?
+            $(, placeholder: { $($placeholder_field:ident: $placeholder:expr),* $(,)? })?
+            $(, tags: [$($tag:literal),* $(,)?])?
+            $(, validate: { $($validate_field:ident: $validate_rule:expr => $validate_message:literal),* $(,)? })?
+            $(, related: { $($related_name:ident: $related_entity:path),* $(,)? })?
+            $(, run_model_hooks: $run_hooks:literal)?
+            $(, id_newtype: $id_newtype:ident)?
+            $(, nullable: [$($nullable_field:ident),* $(,)?])?
+            $(, base: $base:ident)?
+            $(, unique_suffix: $unique_suffix_field:ident)?
+            $(, polymorphic: { $($poly_name:ident: ($poly_type_field:ident, $poly_id_field:ident)),* $(,)? })?
+            $(, lazy_when: { $($when_field:ident: $when_predicate:expr => $when_sentinel:expr),* $(,)? })?
+            $(, belongs_to: { $($parent_fk:ident: $parent_factory:ident),* $(,)? })?
+            $(, conn_accessor: $conn_accessor:literal)?
+            $(, preflight_unique: [$($preflight_field:ident),* $(,)?])?
+            $(, relations: { $($relation_fk:ident: $relation_factory:ident $(($relation_strategy:ident))?),* $(,)? })?
+            $(, has_many: [$($has_many_column:path),* $(,)?])?
+            $(, builder_name: $builder_name:ident)?
         }
     ) => {
-        ::paste::paste! {
+        $crate::__private::paste::paste! {
+            // Aplica o allow(dead_code) aos itens gerados dentro de repetições, exceto no modo público
+            #[allow(unused_macros)]
+            macro_rules! [<__ $fn_name _factory_item>] {
+                ($item:item) => {
+                    #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+                    $item
+                };
+            }
+
+            // Repete a definição desta factory para as que a estendem, sem os nomes próprios dela
+            // (`public`, `plural`, `id_newtype` e `builder_name`)
+            #[allow(unused_macros)]
+            macro_rules! [<__ $fn_name _extend>] {
+                ($extension:tt) => {
+                    $crate::define_factory!(@extend $extension {
+                        active_model: $active_model,
+                        fields: {
+                            $($(#[doc = $field_doc])* $field: $field_type = $default),*
+                        }
+                        [
+                            $(, lazy_fields: {
+                                $($(#[doc = $lazy_doc])* $lazy_field: $lazy_type $(= $lazy_default)? $(=> $partial_default)?),*
+                            })?
+                            $(, insert_with: $insert_fn)?
+                            $(, unique: [$($unique_field),*])?
+                            $(, complete: $complete)?
+                            $(, primary_key: {
+                                $pk_field: $pk_type $(, wrap: $pk_wrap)? $(, explicit: $pk_explicit)?
+                            })?
+                            $(, arbitrary: { $($arb_field: $arb_strategy),* })?
+                            $(, retry_on_conflict: $retries)?
+                        ]
+                        [
+                            $(, generated: { $($generated_field),* })?
+                            $(, soft_delete: $soft_delete_field)?
+                            $(, transform: { $($transform_field: $transform_fn),* })?
+                            $(, returns: $returns, map: $returns_map)?
+                            $(, lazy_defaults: $lazy_defaults)?
+                            $(, route: $route)?
+                            $(, label_field: $label_field)?
+                            $(, placeholder: { $($placeholder_field: $placeholder),* })?
+                            $(, tags: [$($tag),*])?
+                            $(, validate: { $($validate_field: $validate_rule => $validate_message),* })?
+                            $(, related: { $($related_name: $related_entity),* })?
+                            $(, run_model_hooks: $run_hooks)?
+                            $(, nullable: [$($nullable_field),*])?
+                            $(, base: $base)?
+                            $(, unique_suffix: $unique_suffix_field)?
+                            $(, polymorphic: { $($poly_name: ($poly_type_field, $poly_id_field)),* })?
+                            $(, lazy_when: { $($when_field: $when_predicate => $when_sentinel),* })?
+                            $(, belongs_to: { $($parent_fk: $parent_factory),* })?
+                            $(, conn_accessor: $conn_accessor)?
+                            $(, preflight_unique: [$($preflight_field),*])?
+                            $(, relations: { $($relation_fk: $relation_factory $(($relation_strategy))?),* })?
+                            $(, has_many: [$($has_many_column),*])?
+                        ]
+                    });
+                };
+            }
+
             // Função factory principal
             $(#[$meta])*
-            pub async fn [<create_ $fn_name>](db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr> {
-                type Active = $active_model;
-                let model = Active {
-                    $(
-                        $field: sea_orm::ActiveValue::Set($default),
-                    )*
-                    $($(
-                        $lazy_field: sea_orm::ActiveValue::Set($lazy_default(db).await?),
-                    )*)?
-                    ..Default::default()
-                };
-                model.insert(db).await
+            pub async fn [<create_ $fn_name>](
+                db: &$crate::define_factory!(@conn_type [$($conn_accessor)?]),
+            ) -> Result<$model, sea_orm::DbErr> {
+                let db = $crate::PrimaryConn::primary(db);
+                let backend = sea_orm::ConnectionTrait::get_database_backend(db);
+                $crate::backend::with_backend(backend, [<Create $fn_name:camel Builder>]::new)
+                    .create(db)
+                    .await
             }
 
+            // Tipo da chave primária, usado pelo `id_newtype`
+            #[allow(dead_code)]
+            type [<__ $fn_name:camel PrimaryKey>] = $crate::define_factory!(@pk_type $($pk_type)?);
+
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Id da factory como tipo próprio (cláusula `id_newtype`)
+                    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+                    pub struct $id_newtype(pub [<__ $fn_name:camel PrimaryKey>]);
+                }
+
+                impl From<[<__ $fn_name:camel PrimaryKey>]> for $id_newtype {
+                    fn from(id: [<__ $fn_name:camel PrimaryKey>]) -> Self {
+                        $id_newtype(id)
+                    }
+                }
+
+                impl From<$id_newtype> for [<__ $fn_name:camel PrimaryKey>] {
+                    fn from(id: $id_newtype) -> Self {
+                        id.0
+                    }
+                }
+            )?
+
+            // Tipos dos campos regulares, usados pelos helpers que recebem valores desses campos
+            $(
+                #[allow(dead_code)]
+                type [<__ $fn_name:camel $field:camel Type>] = $field_type;
+            )*
+
             // Builder struct
             #[derive(Debug, Clone)]
-            #[allow(dead_code)]
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
             pub struct [<Create $fn_name:camel Builder>] {
                 $(
                     $field: $field_type,
@@ -315,633 +1024,6773 @@ macro_rules! define_factory {
                 $($(
                     $lazy_field: Option<$lazy_type>,
                 )*)?
+                // Closures async dos setters `set_<campo>_with`, resolvidas no create
+                $($(
+                    [<__ $lazy_field _with>]: Option<$crate::lazy::LazyWith<$lazy_type>>,
+                )*)?
+                // Campos definidos explicitamente via setters
+                __overridden: ::std::collections::BTreeSet<&'static str>,
+                // Valores padrão calculados em new()
+                __defaults: ::std::boxed::Box<[<$fn_name:camel Params>]>,
+                // Campos emitidos como NotSet, para o banco aplicar seu próprio default
+                __unset: ::std::collections::BTreeSet<&'static str>,
+                // Schema onde o registro é inserido (apenas Postgres)
+                __schema: Option<String>,
+                // Política de nova tentativa do insert, definida por `with_retry`
+                __retry: Option<$crate::retry::RetryPolicy>,
+                // Backend sob o qual os defaults atuais foram calculados
+                __backend: sea_orm::DatabaseBackend,
+                // Builders dos pais criados antes do insert, definidos por `with_<pai>`
+                $($(
+                    [<__ $relation_factory _parent>]: Option<::std::boxed::Box<[<Create $relation_factory:camel Builder>]>>,
+                )*)?
             }
 
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Nome do builder fixado pela cláusula `builder_name`
+                    pub type $builder_name = [<Create $fn_name:camel Builder>];
+                }
+            )?
+
             impl Default for [<Create $fn_name:camel Builder>] {
                 fn default() -> Self {
                     Self::new()
                 }
             }
 
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
             impl [<Create $fn_name:camel Builder>] {
                 /// Cria um novo builder com valores padrão
                 pub fn new() -> Self {
+                    Self::__with_defaults([<$fn_name:camel Params>]::__evaluate_defaults())
+                }
+
+                // Builder com os valores de um registro existente, sem avaliar os defaults da factory
+                fn __from_model(model: &$model) -> Self {
+                    Self::__with_defaults([<$fn_name:camel Params>] {
+                        $(
+                            $field: ::core::clone::Clone::clone(&model.$field),
+                        )*
+                        $($(
+                            $lazy_field: None,
+                        )*)?
+                    })
+                }
+
+                // Builder sem campos sobrescritos, partindo dos valores dados
+                fn __with_defaults(defaults: [<$fn_name:camel Params>]) -> Self {
                     Self {
                         $(
-                            $field: $default,
+                            $field: defaults.$field.clone(),
                         )*
                         $($(
                             $lazy_field: None,
                         )*)?
+                        $($(
+                            [<__ $lazy_field _with>]: None,
+                        )*)?
+                        __overridden: ::std::collections::BTreeSet::new(),
+                        __defaults: ::std::boxed::Box::new(defaults),
+                        __unset: ::std::collections::BTreeSet::new(),
+                        __schema: None,
+                        __retry: None,
+                        __backend: $crate::backend::current_backend().unwrap_or(sea_orm::DatabaseBackend::Sqlite),
+                        $($(
+                            [<__ $relation_factory _parent>]: None,
+                        )*)?
                     }
                 }
 
                 $(
-                    /// Define o valor de $field
-                    #[allow(dead_code)]
-                    pub fn $field(mut self, value: $field_type) -> Self {
-                        self.$field = value;
+                    #[doc = $crate::define_factory!(@setter_doc $field, "Define o valor de"; $($field_doc)*)]
+                    #[allow(clippy::wrong_self_convention)]
+                    pub fn $field(mut self, value: impl Into<$field_type>) -> Self {
+                        self.$field = value.into();
+                        self.__overridden.insert(stringify!($field));
+                        self.__unset.remove(stringify!($field));
+                        self.__evaluate_defaults(Some(stringify!($field)));
                         self
                     }
                 )*
 
                 $($(
-                    /// Define o valor de $lazy_field
-                    pub fn $lazy_field(mut self, value: $lazy_type) -> Self {
-                        self.$lazy_field = Some(value);
+                    #[doc = $crate::define_factory!(@setter_doc $lazy_field, "Define o valor de"; $($lazy_doc)*)]
+                    pub fn $lazy_field(mut self, value: impl Into<$lazy_type>) -> Self {
+                        self.$lazy_field = Some(value.into());
+                        self.__overridden.insert(stringify!($lazy_field));
+                        self.__unset.remove(stringify!($lazy_field));
                         self
                     }
                 )*)?
 
-                /// Constrói e salva o model no banco
-                pub async fn create(self, db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr> {
-                    type Active = $active_model;
-                    Active {
-                        $(
-                            $field: sea_orm::ActiveValue::Set(self.$field),
-                        )*
-                        $($(
-                            $lazy_field: sea_orm::ActiveValue::Set(
-                                match self.$lazy_field {
-                                    Some(val) => val,
-                                    None => $lazy_default(db).await?,
-                                }
-                            ),
-                        )*)?
-                        ..Default::default()
+                $(
+                    #[doc = $crate::define_factory!(@setter_doc $field, "Define o valor de", " sem consumir o builder"; $($field_doc)*)]
+                    pub fn [<set_ $field>](&mut self, value: impl Into<$field_type>) -> &mut Self {
+                        self.$field = value.into();
+                        self.__overridden.insert(stringify!($field));
+                        self.__unset.remove(stringify!($field));
+                        self.__evaluate_defaults(Some(stringify!($field)));
+                        self
                     }
-                    .insert(db)
-                    .await
-                }
+                )*
 
-                /// Constrói o model sem salvar (requer todos os valores lazy)
-                #[allow(dead_code)]
-                pub fn build(self) -> $active_model {
-                    type Active = $active_model;
-                    Active {
-                        $(
-                            $field: sea_orm::ActiveValue::Set(self.$field),
-                        )*
-                        $($(
-                            $lazy_field: sea_orm::ActiveValue::Set(
-                                self.$lazy_field.expect(concat!("lazy field '", stringify!($lazy_field), "' must be set when using build()"))
-                            ),
-                        )*)?
-                        ..Default::default()
+                $($(
+                    #[doc = $crate::define_factory!(@setter_doc $lazy_field, "Define o valor de", " sem consumir o builder"; $($lazy_doc)*)]
+                    pub fn [<set_ $lazy_field>](&mut self, value: impl Into<$lazy_type>) -> &mut Self {
+                        self.$lazy_field = Some(value.into());
+                        self.__overridden.insert(stringify!($lazy_field));
+                        self.__unset.remove(stringify!($lazy_field));
+                        self
                     }
-                }
-            }
+                )*)?
 
-            /// Helper function para criar o builder
-            #[allow(dead_code)]
-            pub fn [<create_ $fn_name _builder>]() -> [<Create $fn_name:camel Builder>] {
-                [<Create $fn_name:camel Builder>]::new()
-            }
+                $($(
+                    /// Define o valor por uma closure async que recebe a conexão, resolvida no `create`
+                    pub fn [<set_ $lazy_field _with>]<F, Fut>(mut self, f: F) -> Self
+                    where
+                        F: Fn(sea_orm::DatabaseConnection) -> Fut + Send + Sync + 'static,
+                        Fut: ::std::future::Future<Output = Result<$lazy_type, sea_orm::DbErr>> + Send + 'static,
+                    {
+                        self.$lazy_field = None;
+                        self.[<__ $lazy_field _with>] = Some($crate::lazy::LazyWith::new(f));
+                        self.__overridden.insert(stringify!($lazy_field));
+                        self.__unset.remove(stringify!($lazy_field));
+                        self
+                    }
+                )*)?
 
-            /// Helper function to get model id
-            #[allow(dead_code)]
-            pub async fn [<get_ $fn_name _id>](db: &sea_orm::DatabaseConnection) -> Result<i32, sea_orm::DbErr> {
-                [<create_ $fn_name>](db).await.map(|s| s.id)
-            }
-        }
-    };
-}
+                $(
+                    #[doc = concat!("Não define `", stringify!($field), "`, deixando o banco aplicar seu default")]
+                    pub fn [<unset_ $field>](mut self) -> Self {
+                        self.__unset.insert(stringify!($field));
+                        self.__overridden.remove(stringify!($field));
+                        self
+                    }
+                )*
 
-// ============================================
-// DEFININDO AS FACTORIES
-// ============================================
+                $($(
+                    #[doc = concat!("Não define `", stringify!($lazy_field), "`, deixando o banco aplicar seu default")]
+                    pub fn [<unset_ $lazy_field>](mut self) -> Self {
+                        self.__unset.insert(stringify!($lazy_field));
+                        self.__overridden.remove(stringify!($lazy_field));
+                        self
+                    }
+                )*)?
+
+                /// Extrai os valores do builder como Params, sem resolver os campos lazy
+                pub fn into_params(self) -> [<$fn_name:camel Params>] {
+                    self.into()
+                }
+
+                /// Copia de `params` os campos que foram definidos nele
+                pub fn spread(mut self, params: &[<$fn_name:camel Params>]) -> Self {
+                    #[allow(unused_imports)]
+                    use $crate::diff::{CompareFallback as _, ComparePartialEq as _};
+                    let baseline = [<__ $fn_name:upper _SPREAD_BASELINE>].with(|baseline| baseline.borrow().clone()).unwrap_or_default();
+                    $(
+                        let set = baseline.$field.as_ref().is_none_or(|baseline| {
+                            (&$crate::diff::Compare(&params.$field, baseline))
+                                .differs()
+                                .unwrap_or_else(|| format!("{:?}", params.$field) != format!("{:?}", baseline))
+                        });
+                        if set {
+                            self = self.$field(params.$field.clone());
+                        }
+                    )*
+                    $($(
+                        if let Some(value) = &params.$lazy_field {
+                            self = self.$lazy_field(value.clone());
+                        }
+                    )*)?
+                    self
+                }
+
+                /// Insere o registro na tabela do schema dado (apenas Postgres)
+                pub fn in_schema(mut self, schema: impl Into<String>) -> Self {
+                    self.__schema = Some(schema.into());
+                    self
+                }
+
+                /// Repete o insert em erros transitórios (deadlock, falha de serialização)
+                pub fn with_retry(self, attempts: u32, backoff: ::std::time::Duration) -> Self {
+                    self.with_retry_policy($crate::retry::RetryPolicy::new(attempts, backoff))
+                }
+
+                /// Repete o insert conforme a política dada
+                pub fn with_retry_policy(mut self, policy: $crate::retry::RetryPolicy) -> Self {
+                    self.__retry = Some(policy);
+                    self
+                }
+
+                $($(
+                    #[doc = concat!("Cria o pai `", stringify!($relation_factory), "` antes do insert, customizando seu builder com `f`")]
+                    pub fn [<with_ $relation_factory>](
+                        mut self,
+                        f: impl FnOnce([<Create $relation_factory:camel Builder>]) -> [<Create $relation_factory:camel Builder>],
+                    ) -> Self {
+                        let parent = self
+                            .[<__ $relation_factory _parent>]
+                            .take()
+                            .map_or_else([<Create $relation_factory:camel Builder>]::new, |parent| *parent);
+                        self.[<__ $relation_factory _parent>] = Some(::std::boxed::Box::new(f(parent)));
+                        self
+                    }
+                )*)?
+
+                /// Constrói e salva o model no banco
+                pub async fn create(self, db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr> {
+                    self.__create(db, false).await.map_err(|err| match err {
+                        $crate::FactoryError::Db(err) => err,
+                        err => sea_orm::DbErr::Custom(err.to_string()),
+                    })
+                }
+
+                // `create`, com as verificações de `preflight_unique` quando `preflight` é verdadeiro
+                async fn __create(self, db: &sea_orm::DatabaseConnection, preflight: bool) -> Result<$model, $crate::FactoryError> {
+                    let max_retries: u32 = $crate::define_factory!(@retries $($retries)?);
+                    if max_retries == 0 {
+                        return self.__create_once(db, preflight).await;
+                    }
+                    let backend = sea_orm::ConnectionTrait::get_database_backend(db);
+                    let mut attempt = 0;
+                    loop {
+                        let mut builder = self.clone();
+                        // Cada nova tentativa recalcula os defaults dos campos não sobrescritos
+                        if attempt > 0 {
+                            $crate::backend::with_backend(backend, || builder.refresh_defaults());
+                        }
+                        match builder.__create_once(db, preflight).await {
+                            Err($crate::FactoryError::Db(err)) if attempt < max_retries && $crate::retry::is_unique_violation(&err) => attempt += 1,
+                            result => return result,
+                        }
+                    }
+                }
+
+                // Uma tentativa de `create`, repetida apenas pela política de `with_retry`
+                async fn __create_once(self, db: &sea_orm::DatabaseConnection, preflight: bool) -> Result<$model, $crate::FactoryError> {
+                    let schema = self.__schema.clone();
+                    let retry = self.__retry.unwrap_or_else(|| $crate::retry::RetryPolicy::new(0, ::std::time::Duration::ZERO));
+                    let model = self.__resolve(db).await?;
+                    // Dentro de `txn::run`, grava na transação corrente
+                    match $crate::txn::current() {
+                        Some(txn) => Self::__save(model, schema, retry, &*txn, preflight).await,
+                        None => Self::__save(model, schema, retry, db, preflight).await,
+                    }
+                }
+
+                // Verifica `preflight_unique` e insere o ActiveModel resolvido com a política de retry
+                async fn __save<C: sea_orm::ConnectionTrait>(
+                    model: $active_model,
+                    schema: Option<String>,
+                    retry: $crate::retry::RetryPolicy,
+                    db: &C,
+                    preflight: bool,
+                ) -> Result<$model, $crate::FactoryError> {
+                    if preflight {
+                        Self::__preflight(&model, db).await?;
+                    }
+                    let model = retry
+                        .run(|| {
+                            let model = model.clone();
+                            let schema = schema.clone();
+                            async move {
+                                match schema {
+                                    Some(schema) => Self::__insert_query(model, Some(&schema)).exec_with_returning(db).await,
+                                    None => Self::__insert(model, db).await,
+                                }
+                            }
+                        })
+                        .await?;
+                    Self::__log(&model);
+                    Ok(model)
+                }
+
+                /// Como `create`, verificando antes se os campos de `preflight_unique` já existem no banco
+                pub async fn try_create(self, db: &sea_orm::DatabaseConnection) -> Result<$model, $crate::FactoryError> {
+                    self.__create(db, true).await
+                }
+
+                // Procura no banco os valores finais dos campos de `preflight_unique`, após lazy fields e `transform`
+                #[allow(unused_variables)]
+                async fn __preflight<C: sea_orm::ConnectionTrait>(model: &$active_model, db: &C) -> Result<(), $crate::FactoryError> {
+                    $($(
+                        if let sea_orm::ActiveValue::Set(value) | sea_orm::ActiveValue::Unchanged(value) = &model.$preflight_field {
+                            type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                            type Column = <Entity as sea_orm::EntityTrait>::Column;
+                            let existing = sea_orm::QueryFilter::filter(
+                                <Entity as sea_orm::EntityTrait>::find(),
+                                sea_orm::ColumnTrait::eq(&Column::[<$preflight_field:camel>], ::core::clone::Clone::clone(value)),
+                            )
+                            .one(db)
+                            .await?;
+                            if existing.is_some() {
+                                return Err($crate::FactoryError::Duplicate { field: stringify!($preflight_field) });
+                            }
+                        }
+                    )*)?
+                    Ok(())
+                }
+
+                $(
+                    /// Cria os pais configurados em `f`, liga suas chaves estrangeiras e salva o model
+                    pub async fn create_with_relations(
+                        mut self,
+                        db: &sea_orm::DatabaseConnection,
+                        f: impl FnOnce([<$fn_name:camel Relations>]) -> [<$fn_name:camel Relations>],
+                    ) -> Result<$model, sea_orm::DbErr> {
+                        let relations = f([<$fn_name:camel Relations>]::default());
+                        $(
+                            if let Some(customize) = relations.$relation_factory {
+                                self = self.[<with_ $relation_factory>](customize);
+                            }
+                        )*
+                        self.create(db).await
+                    }
+                )?
+
+                /// Constrói, salva e relê o registro pela chave primária, trazendo colunas calculadas pelo banco
+                pub async fn create_and_refetch(self, db: &sea_orm::DatabaseConnection) -> Result<$model, sea_orm::DbErr> {
+                    type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                    let created = self.create(db).await?;
+                    <Entity as sea_orm::EntityTrait>::find_by_id(Self::__primary_key(&created))
+                        .one(db)
+                        .await?
+                        .ok_or_else(|| {
+                            sea_orm::DbErr::RecordNotFound(concat!(stringify!($fn_name), " not found after insert").to_string())
+                        })
+                }
+
+                $($(
+                    #[doc = concat!("Define juntos `", stringify!($poly_type_field), "` e `", stringify!($poly_id_field), "` da associação polimórfica")]
+                    pub fn [<for_ $poly_name>](
+                        self,
+                        kind: impl Into<[<__ $fn_name:camel $poly_type_field:camel Type>]>,
+                        id: impl Into<[<__ $fn_name:camel $poly_id_field:camel Type>]>,
+                    ) -> Self {
+                        self.$poly_type_field(kind).$poly_id_field(id)
+                    }
+                )*)?
+
+                $(
+                    /// Cria `count` registros deste builder, acrescentando `-{i}` ao campo `unique_suffix`
+                    pub async fn create_list_unique_suffix(
+                        self,
+                        db: &sea_orm::DatabaseConnection,
+                        count: usize,
+                    ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                        let mut models = Vec::with_capacity(count);
+                        for i in 0..count {
+                            let value = format!("{}-{}", self.$unique_suffix_field, i);
+                            models.push(self.clone().$unique_suffix_field(value).create(db).await?);
+                        }
+                        Ok(models)
+                    }
+                )?
+
+                /// Statement INSERT que o builder executaria (requer todos os valores lazy)
+                pub fn insert_statement(self, backend: sea_orm::DatabaseBackend) -> sea_orm::Statement {
+                    let schema = self.__schema.clone();
+                    let insert = Self::__insert_query(self.build(), schema.as_deref());
+                    sea_orm::QueryTrait::build(&insert, backend)
+                }
+
+                // Monta o INSERT do model, qualificando a tabela com o schema quando dado
+                fn __insert_query(model: $active_model, schema: Option<&str>) -> sea_orm::Insert<$active_model> {
+                    type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                    let mut insert = <Entity as sea_orm::EntityTrait>::insert(model);
+                    if let Some(schema) = schema {
+                        sea_orm::QueryTrait::query(&mut insert)
+                            .into_table((sea_orm::sea_query::Alias::new(schema), Entity::default()));
+                    }
+                    insert
+                }
+
+                /// Constrói, salva e converte o model pela cláusula `returns`
+                pub async fn create_mapped(
+                    self,
+                    db: &sea_orm::DatabaseConnection,
+                ) -> Result<$crate::define_factory!(@returns_type $model $(, $returns)?), sea_orm::DbErr> {
+                    let model = self.create(db).await?;
+                    Ok($crate::define_factory!(@returns_map model $(, $returns_map)?))
+                }
+
+                /// Constrói, salva e converte o model com `f` (ex.: para um DTO)
+                pub async fn create_as<T>(
+                    self,
+                    db: &sea_orm::DatabaseConnection,
+                    f: impl FnOnce($model) -> T,
+                ) -> Result<T, sea_orm::DbErr> {
+                    self.create(db).await.map(f)
+                }
+
+                $(
+                    /// Constrói e salva o model na conexão escolhida pela cláusula `route`
+                    pub async fn create_routed(self, router: &$crate::DbRouter) -> Result<$model, sea_orm::DbErr> {
+                        let db = $crate::routing::route(&self.__routing_partial(), router, $route);
+                        self.create(db).await
+                    }
+                )?
+
+                // ActiveModel usado pela cláusula `route`, com os campos lazy ainda NotSet
+                #[allow(unused_mut)]
+                fn __routing_partial(&self) -> $active_model {
+                    let mut partial = self.clone();
+                    $($(
+                        partial.__unset.insert(stringify!($lazy_field));
+                    )*)?
+                    partial.build()
+                }
+
+                /// Cria o registro apenas quando `cond` é verdadeiro
+                pub async fn create_if(
+                    self,
+                    db: &sea_orm::DatabaseConnection,
+                    cond: bool,
+                ) -> Result<Option<$model>, sea_orm::DbErr> {
+                    if cond {
+                        self.create(db).await.map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                }
+
+                // Monta o ActiveModel, resolvendo os campos lazy não definidos
+                #[allow(unused_variables)]
+                async fn __resolve(mut self, db: &sea_orm::DatabaseConnection) -> Result<$active_model, sea_orm::DbErr> {
+                    $($(
+                        if let Some(parent) = self.[<__ $relation_factory _parent>].take() {
+                            let parent = parent.create(db).await?;
+                            self = self.$relation_fk([<Create $relation_factory:camel Builder>]::__primary_key(&parent));
+                        }
+                        $crate::define_factory!(
+                            @relation_strategy self, db, $relation_fk, [<get_or_create_ $relation_factory _id>] $(, $relation_strategy)?
+                        );
+                    )*)?
+                    self.__refresh_defaults(sea_orm::ConnectionTrait::get_database_backend(db));
+                    type Active = $active_model;
+                    #[allow(unused_mut)]
+                    let mut model = $crate::define_factory!(@active_model Active {
+                        $(
+                            $field: $crate::define_factory!(@value self.__unset, $field, self.$field),
+                        )*
+                        $($(
+                            $lazy_field: sea_orm::ActiveValue::NotSet,
+                        )*)?
+                        $($(
+                            $generated_field: sea_orm::ActiveValue::NotSet,
+                        )*)?
+                        $(
+                            $soft_delete_field: sea_orm::ActiveValue::Set(None),
+                        )?
+                    } [$($complete)?] [$($base)?]);
+                    #[allow(unused_mut, unused_variables)]
+                    let mut skipped = ::std::collections::BTreeSet::<&'static str>::new();
+                    $($(
+                        if self.$when_field.is_none()
+                            && self.[<__ $when_field _with>].is_none()
+                            && !$crate::lazy::check_when(&model, $when_predicate)
+                        {
+                            model.$when_field = sea_orm::ActiveValue::Set($when_sentinel);
+                            skipped.insert(stringify!($when_field));
+                        }
+                    )*)?
+                    $($(
+                        if !self.__unset.contains(stringify!($lazy_field)) && !skipped.contains(stringify!($lazy_field)) {
+                            model.$lazy_field = sea_orm::ActiveValue::Set(match (self.$lazy_field, &self.[<__ $lazy_field _with>]) {
+                                (Some(val), _) => val,
+                                (None, Some(with)) => with.resolve(db).await?,
+                                (None, None) => $crate::define_factory!(@lazy_value db, model, [$($lazy_default)?] [$($partial_default)?]),
+                            });
+                        }
+                    )*)?
+                    $crate::define_factory!(@primary_key_not_set model, [$($($pk_explicit)?)?] $($pk_field)?);
+                    $crate::define_factory!(@transform model $(, $($transform_field: $transform_fn),*)?);
+                    Ok(model)
+                }
+
+                // Recalcula os defaults dos campos não sobrescritos sob `backend`, com `lazy_defaults: true`
+                // ou quando foram calculados para outro backend
+                fn __refresh_defaults(&mut self, backend: sea_orm::DatabaseBackend) {
+                    if $crate::define_factory!(@lazy_defaults $($lazy_defaults)?) || self.__backend != backend {
+                        $crate::backend::with_backend(backend, || self.refresh_defaults());
+                        self.__backend = backend;
+                    }
+                }
+
+                /// Recalcula os valores padrão dos campos não definidos via setter
+                pub fn refresh_defaults(&mut self) -> &mut Self {
+                    self.__evaluate_defaults(None);
+                    self
+                }
+
+                // Avalia os defaults na ordem dos campos, lendo os valores sobrescritos dos campos anteriores:
+                // todos os não sobrescritos ou, após o setter de `changed`, só os que dependem dele
+                #[allow(unused_mut, unused_variables, clippy::clone_on_copy)]
+                fn __evaluate_defaults(&mut self, changed: Option<&'static str>) {
+                    let mut stale = ::std::collections::BTreeSet::<&'static str>::new();
+                    stale.extend(changed);
+                    $(
+                        let $field: $field_type = if self.__overridden.contains(stringify!($field)) {
+                            self.$field.clone()
+                        } else if changed.is_none()
+                            || stale.iter().any(|name| $crate::defaults::mentions(stringify!($default), name))
+                        {
+                            stale.insert(stringify!($field));
+                            $crate::overrides::or_default(stringify!($field), || $default)
+                        } else {
+                            self.$field.clone()
+                        };
+                    )*
+                    $(
+                        if !self.__overridden.contains(stringify!($field)) && stale.contains(stringify!($field)) {
+                            if changed.is_some() {
+                                self.__defaults.$field = $field.clone();
+                            }
+                            self.$field = $field;
+                        }
+                    )*
+                }
+
+                // Salva o ActiveModel na conexão dada (banco ou transação)
+                async fn __insert<C: sea_orm::ConnectionTrait>(model: $active_model, db: &C) -> Result<$model, sea_orm::DbErr> {
+                    $crate::define_factory!(@insert model, db, [$($run_hooks)?] $($insert_fn)?)
+                }
+
+                // Chave primária do model, sem a conversão de `wrap`
+                #[doc(hidden)]
+                #[allow(clippy::clone_on_copy)]
+                pub fn __primary_key(model: &$model) -> $crate::define_factory!(@pk_type $($pk_type)?) {
+                    $crate::define_factory!(@pk_value model $(, $pk_field)?).clone()
+                }
+
+                // Registra o model criado no log da feature `factory-log`
+                #[allow(unused_variables)]
+                fn __log(model: &$model) {
+                    $crate::__cfg_factory_log! {
+                        $crate::factory_log::record(model, $crate::define_factory!(@pk_column $model $(, [<$pk_field:camel>])?));
+                    }
+                }
+
+                /// Lista os campos cujo valor difere do padrão da factory
+                pub fn diff_from_defaults(&self) -> Vec<&'static str> {
+                    #[allow(unused_imports)]
+                    use $crate::diff::{CompareFallback as _, ComparePartialEq as _};
+                    #[allow(unused_mut)]
+                    let mut fields = Vec::new();
+                    $(
+                        if (&$crate::diff::Compare(&self.$field, &self.__defaults.$field)).differs() == Some(true) {
+                            fields.push(stringify!($field));
+                        }
+                    )*
+                    $($(
+                        if self.$lazy_field.is_some() || self.[<__ $lazy_field _with>].is_some() {
+                            fields.push(stringify!($lazy_field));
+                        }
+                    )*)?
+                    fields
+                }
+
+                /// Constrói, salva o model no banco e retorna apenas o id
+                pub async fn create_returning_id(
+                    self,
+                    db: &sea_orm::DatabaseConnection,
+                ) -> Result<$crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?), sea_orm::DbErr> {
+                    self.create(db)
+                        .await
+                        .map(|model| $crate::define_factory!(@id_value [$($id_newtype)?] model $(, $pk_field $(, $pk_wrap)?)?))
+                }
+
+                /// Indica se `create` precisa resolver algum campo lazy no banco
+                pub fn requires_db(&self) -> bool {
+                    false $($(
+                        || (self.$lazy_field.is_none() && !self.__unset.contains(stringify!($lazy_field)))
+                    )*)?
+                }
+
+                /// Monta o Model com os valores atuais, sem banco (requer todos os valores lazy)
+                pub fn preview(&self) -> $model {
+                    type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                    type Column = <Entity as sea_orm::EntityTrait>::Column;
+                    let built = self.clone().build();
+                    let mut active = <$active_model as sea_orm::ActiveModelTrait>::default_values();
+                    for column in <Column as sea_orm::Iterable>::iter() {
+                        if let sea_orm::ActiveValue::Set(value) = sea_orm::ActiveModelTrait::get(&built, column) {
+                            sea_orm::ActiveModelTrait::set(&mut active, column, value);
+                        }
+                    }
+                    sea_orm::TryIntoModel::try_into_model(active)
+                        .expect("every column not set by the factory must implement Default to preview the model")
+                }
+
+                /// Constrói o model sem salvar, com erro em vez de panic quando falta um valor lazy
+                pub fn try_build(self) -> Result<$active_model, $crate::FactoryError> {
+                    $($(
+                        if self.$lazy_field.is_none() && !self.__unset.contains(stringify!($lazy_field)) {
+                            return Err($crate::FactoryError::MissingLazyField(stringify!($lazy_field)));
+                        }
+                    )*)?
+                    Ok(self.build())
+                }
+
+                /// Constrói um model só com os campos definidos por setter, os demais ficam `NotSet`
+                pub fn build_changes(self) -> $active_model {
+                    #[allow(unused_mut)]
+                    let mut active = <$active_model as sea_orm::ActiveModelTrait>::default();
+                    $(
+                        if self.__overridden.contains(stringify!($field)) {
+                            active.$field = sea_orm::ActiveValue::Set(self.$field);
+                        }
+                    )*
+                    $($(
+                        if let Some(value) = self.$lazy_field {
+                            active.$lazy_field = sea_orm::ActiveValue::Set(value);
+                        }
+                    )*)?
+                    $crate::define_factory!(@transform active $(, $($transform_field: $transform_fn),*)?);
+                    active
+                }
+
+                /// Constrói o model após checar todas as regras de `validate`, retornando todos os erros
+                pub fn validate_then_build(self) -> Result<$active_model, Vec<$crate::FieldError>> {
+                    #[allow(unused_mut)]
+                    let mut errors = Vec::new();
+                    $($(
+                        if !$crate::error::check(&self.$validate_field, $validate_rule) {
+                            errors.push($crate::FieldError {
+                                field: stringify!($validate_field),
+                                message: $validate_message.to_string(),
+                            });
+                        }
+                    )*)?
+                    if errors.is_empty() {
+                        Ok(self.build())
+                    } else {
+                        Err(errors)
+                    }
+                }
+
+                /// Constrói o model sem salvar, usando o `placeholder` dos campos lazy não definidos
+                #[allow(unused_mut)]
+                pub fn build_placeholder(mut self) -> $active_model {
+                    $($(
+                        if self.$placeholder_field.is_none() {
+                            self.$placeholder_field = Some($placeholder);
+                        }
+                    )*)?
+                    self.build()
+                }
+
+                /// Constrói o model sem salvar (requer todos os valores lazy)
+                pub fn build(mut self) -> $active_model {
+                    self.__refresh_defaults(self.__backend);
+                    type Active = $active_model;
+                    #[allow(unused_mut)]
+                    let mut model = $crate::define_factory!(@active_model Active {
+                        $(
+                            $field: $crate::define_factory!(@value self.__unset, $field, self.$field),
+                        )*
+                        $($(
+                            $lazy_field: $crate::define_factory!(
+                                @value self.__unset, $lazy_field,
+                                self.$lazy_field.expect(concat!("lazy field '", stringify!($lazy_field), "' must be set when using build()"))
+                            ),
+                        )*)?
+                        $($(
+                            $generated_field: sea_orm::ActiveValue::NotSet,
+                        )*)?
+                        $(
+                            $soft_delete_field: sea_orm::ActiveValue::Set(None),
+                        )?
+                    } [$($complete)?] [$($base)?]);
+                    $crate::define_factory!(@primary_key_not_set model, [$($($pk_explicit)?)?] $($pk_field)?);
+                    $crate::define_factory!(@transform model $(, $($transform_field: $transform_fn),*)?);
+                    model
+                }
+            }
+
+            // Converte o builder com a mesma lógica de build(), com panic se faltar um valor lazy
+            impl From<[<Create $fn_name:camel Builder>]> for $active_model {
+                fn from(builder: [<Create $fn_name:camel Builder>]) -> Self {
+                    builder.build()
+                }
+            }
+
+            /// Cria um registro e o converte pela cláusula `returns`
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _mapped>](
+                db: &sea_orm::DatabaseConnection,
+            ) -> Result<$crate::define_factory!(@returns_type $model $(, $returns)?), sea_orm::DbErr> {
+                let model = [<create_ $fn_name>](db).await?;
+                Ok($crate::define_factory!(@returns_map model $(, $returns_map)?))
+            }
+
+            /// Cria um registro e um guard que o remove na limpeza
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _guarded>](
+                db: &sea_orm::DatabaseConnection,
+            ) -> Result<($model, $crate::CleanupGuard), sea_orm::DbErr> {
+                let model = [<create_ $fn_name>](db).await?;
+                let guard = $crate::CleanupGuard::new(db, model.clone());
+                Ok((model, guard))
+            }
+
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro e um guard que remove, na limpeza, os filhos de `has_many` e depois o registro
+                    pub async fn [<create_ $fn_name _guarded_cascade>](
+                        db: &sea_orm::DatabaseConnection,
+                    ) -> Result<($model, $crate::CleanupGuard), sea_orm::DbErr> {
+                        let model = [<create_ $fn_name>](db).await?;
+                        let guard = $crate::CleanupGuard::new(db, model.clone())
+                            $(.with_children($has_many_column))*;
+                        Ok((model, guard))
+                    }
+                }
+            )?
+
+            /// Converte o nome de um campo da factory na variante de `Column` da entidade
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<$fn_name _column_of>](
+                field: &str,
+            ) -> Option<<<$model as sea_orm::ModelTrait>::Entity as sea_orm::EntityTrait>::Column> {
+                type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                type Column = <Entity as sea_orm::EntityTrait>::Column;
+                match field {
+                    $(
+                        stringify!($field) => Some(Column::[<$field:camel>]),
+                    )*
+                    $($(
+                        stringify!($lazy_field) => Some(Column::[<$lazy_field:camel>]),
+                    )*)?
+                    $($(
+                        stringify!($generated_field) => Some(Column::[<$generated_field:camel>]),
+                    )*)?
+                    $(
+                        stringify!($soft_delete_field) => Some(Column::[<$soft_delete_field:camel>]),
+                    )?
+                    _ => None,
+                }
+            }
+
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro na conexão escolhida pela cláusula `route`
+                    pub async fn [<create_ $fn_name _routed>](router: &$crate::DbRouter) -> Result<$model, sea_orm::DbErr> {
+                        let builder = [<Create $fn_name:camel Builder>]::new();
+                        let db = $crate::routing::route(&builder.__routing_partial(), router, $route);
+                        builder.create(db).await
+                    }
+                }
+            )?
+
+            $crate::__cfg_log! {
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro, registrando o erro no `log` e retornando `None` em caso de falha
+                    pub async fn [<create_ $fn_name _or_log>](db: &sea_orm::DatabaseConnection) -> Option<$model> {
+                        $crate::or_log::log_failure(stringify!($fn_name), [<create_ $fn_name>](db).await)
+                    }
+                }
+            }
+
+            $crate::__cfg_loco! {
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro na conexão do `AppContext` do Loco
+                    pub async fn [<create_ $fn_name _ctx>](
+                        ctx: &$crate::__private::loco_rs::app::AppContext,
+                    ) -> Result<$model, sea_orm::DbErr> {
+                        [<create_ $fn_name>](&ctx.db).await
+                    }
+                }
+            }
+
+            $crate::__cfg_summary! {
+                [<__ $fn_name _factory_item>]! {
+                    /// Resume o registro em uma linha: chave primária e `label_field`
+                    pub fn [<summarize_ $fn_name>](model: &$model) -> String {
+                        #[allow(unused_mut)]
+                        let mut summary = format!(
+                            "{} {{ {}: {:?}",
+                            stringify!($fn_name),
+                            $crate::define_factory!(@pk_name $($pk_field)?),
+                            $crate::define_factory!(@pk_value model $(, $pk_field)?),
+                        );
+                        $(
+                            summary.push_str(&format!(", {}: {:?}", stringify!($label_field), model.$label_field));
+                        )?
+                        summary.push_str(" }");
+                        summary
+                    }
+                }
+            }
+
+            /// Helper function para criar o builder
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<create_ $fn_name _builder>]() -> [<Create $fn_name:camel Builder>] {
+                [<Create $fn_name:camel Builder>]::new()
+            }
+
+            ::std::thread_local! {
+                static [<__ $fn_name:upper _SEQUENCE>]: ::std::cell::Cell<u64> = const { ::std::cell::Cell::new(0) };
+            }
+
+            /// Próximo valor da sequência da factory, começando em 1 (por thread)
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<$fn_name _sequence_next>]() -> u64 {
+                $crate::sequence::register(
+                    concat!(module_path!(), "::", stringify!($fn_name)),
+                    [<reset_ $fn_name _sequence>],
+                );
+                [<__ $fn_name:upper _SEQUENCE>].with(|sequence| {
+                    sequence.set(sequence.get() + 1);
+                    sequence.get()
+                })
+            }
+
+            /// Reinicia a sequência da factory
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<reset_ $fn_name _sequence>]() {
+                [<__ $fn_name:upper _SEQUENCE>].with(|sequence| sequence.set(0));
+            }
+
+            /// Reinicia a sequência agora e ao sair do escopo
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<$fn_name _sequence_scope>]() -> $crate::SequenceScope {
+                $crate::SequenceScope::new([<reset_ $fn_name _sequence>])
+            }
+
+            /// Helper function to get model id
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<get_ $fn_name _id>](
+                db: &sea_orm::DatabaseConnection,
+            ) -> Result<$crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?), sea_orm::DbErr> {
+                [<create_ $fn_name>](db)
+                    .await
+                    .map(|s| $crate::define_factory!(@id_value [$($id_newtype)?] s $(, $pk_field $(, $pk_wrap)?)?))
+            }
+
+            /// Cria um registro e retorna seu id junto com o model
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _id_and_model>](
+                db: &sea_orm::DatabaseConnection,
+            ) -> Result<($crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?), $model), sea_orm::DbErr> {
+                let model = [<create_ $fn_name>](db).await?;
+                Ok(($crate::define_factory!(@id_value [$($id_newtype)?] model.clone() $(, $pk_field $(, $pk_wrap)?)?), model))
+            }
+
+            $($(
+                [<__ $fn_name _factory_item>]! {
+                    #[doc = concat!(
+                        "Cria um registro e o retorna com `", stringify!($related_name),
+                        "` carregado pela relação (`find_also_related`)"
+                    )]
+                    pub async fn [<create_ $fn_name _with_loaded_ $related_name>](
+                        db: &sea_orm::DatabaseConnection,
+                    ) -> Result<($model, <$related_entity as sea_orm::EntityTrait>::Model), sea_orm::DbErr> {
+                        type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                        let created = [<create_ $fn_name>](db).await?;
+                        let pk = [<Create $fn_name:camel Builder>]::__primary_key(&created);
+                        match <Entity as sea_orm::EntityTrait>::find_by_id(pk)
+                            .find_also_related($related_entity)
+                            .one(db)
+                            .await?
+                        {
+                            Some((model, Some(related))) => Ok((model, related)),
+                            _ => Err(sea_orm::DbErr::RecordNotFound(
+                                concat!(stringify!($related_name), " of ", stringify!($fn_name), " not found").to_string(),
+                            )),
+                        }
+                    }
+                }
+            )*)?
+
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Customização dos builders dos pais criados por `create_with_relations`
+                    #[derive(Default)]
+                    pub struct [<$fn_name:camel Relations>] {
+                        $(
+                            $relation_factory: Option<::std::boxed::Box<
+                                dyn FnOnce([<Create $relation_factory:camel Builder>]) -> [<Create $relation_factory:camel Builder>],
+                            >>,
+                        )*
+                    }
+                }
+
+                [<__ $fn_name _factory_item>]! {
+                    impl [<$fn_name:camel Relations>] {
+                        $(
+                            #[doc = concat!("Cria o pai `", stringify!($relation_factory), "` customizando seu builder com `f`")]
+                            pub fn $relation_factory(
+                                mut self,
+                                f: impl FnOnce([<Create $relation_factory:camel Builder>]) -> [<Create $relation_factory:camel Builder>] + 'static,
+                            ) -> Self {
+                                self.$relation_factory = Some(::std::boxed::Box::new(f));
+                                self
+                            }
+                        )*
+                    }
+                }
+
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro junto com os pais configurados em `f`
+                    pub async fn [<create_ $fn_name _with_relations>](
+                        db: &sea_orm::DatabaseConnection,
+                        f: impl FnOnce([<$fn_name:camel Relations>]) -> [<$fn_name:camel Relations>],
+                    ) -> Result<$model, sea_orm::DbErr> {
+                        [<Create $fn_name:camel Builder>]::new().create_with_relations(db, f).await
+                    }
+                }
+            )?
+
+            $(
+                $(
+                    impl $crate::BelongsTo<<[<Create $parent_factory:camel Builder>] as $crate::batch::BatchInsert>::Model>
+                        for [<Create $fn_name:camel Builder>]
+                    {
+                        fn belongs_to(self, parent: &<[<Create $parent_factory:camel Builder>] as $crate::batch::BatchInsert>::Model) -> Self {
+                            self.$parent_fk([<Create $parent_factory:camel Builder>]::__primary_key(parent))
+                        }
+                    }
+                )*
+
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro ligado ao model pai dado, lendo a chave estrangeira da sua chave primária
+                    pub async fn [<create_ $fn_name _for>]<P>(
+                        db: &sea_orm::DatabaseConnection,
+                        parent: &P,
+                    ) -> Result<$model, sea_orm::DbErr>
+                    where
+                        [<Create $fn_name:camel Builder>]: $crate::BelongsTo<P>,
+                    {
+                        $crate::BelongsTo::belongs_to([<Create $fn_name:camel Builder>]::new(), parent)
+                            .create(db)
+                            .await
+                    }
+                }
+            )?
+
+            $($(
+                [<__ $fn_name _factory_item>]! {
+                    #[doc = concat!("Cria um registro com `", stringify!($nullable_field), "` nulo")]
+                    pub async fn [<create_ $fn_name _with_null_ $nullable_field>](
+                        db: &sea_orm::DatabaseConnection,
+                    ) -> Result<$model, sea_orm::DbErr> {
+                        let mut builder = [<Create $fn_name:camel Builder>]::new();
+                        builder.[<set_ $nullable_field>](None);
+                        builder.create(db).await
+                    }
+                }
+            )*)?
+
+            /// Retorna o id de um registro existente, criando um apenas se a tabela estiver vazia
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<get_or_create_ $fn_name _id>](
+                db: &sea_orm::DatabaseConnection,
+            ) -> Result<$crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?), sea_orm::DbErr> {
+                type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                let existing = match $crate::txn::current() {
+                    Some(txn) => <Entity as sea_orm::EntityTrait>::find().one(&*txn).await?,
+                    None => <Entity as sea_orm::EntityTrait>::find().one(db).await?,
+                };
+                match existing {
+                    Some(existing) => Ok($crate::define_factory!(@id_value [$($id_newtype)?] existing $(, $pk_field $(, $pk_wrap)?)?)),
+                    None => [<get_ $fn_name _id>](db).await,
+                }
+            }
+
+            // Params struct
+            #[derive(Debug, Clone)]
+            #[non_exhaustive]
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub struct [<$fn_name:camel Params>] {
+                $(
+                    pub $field: $field_type,
+                )*
+                $($(
+                    pub $lazy_field: Option<$lazy_type>,
+                )*)?
+            }
+
+            // Valores de partida dos últimos Params criados na thread, lidos pelo `spread` do builder;
+            // `None` marca um campo definido via setter
+            #[derive(Clone, Default)]
+            struct [<__ $fn_name:camel SpreadBaseline>] {
+                $(
+                    $field: Option<$field_type>,
+                )*
+            }
+
+            thread_local! {
+                static [<__ $fn_name:upper _SPREAD_BASELINE>]: ::std::cell::RefCell<Option<[<__ $fn_name:camel SpreadBaseline>]>> =
+                    const { ::std::cell::RefCell::new(None) };
+            }
+
+            impl [<$fn_name:camel Params>] {
+                // Avalia os defaults da factory sem registrá-los como ponto de partida do `spread`
+                fn __evaluate_defaults() -> Self {
+                    $(
+                        let $field: $field_type = $crate::overrides::or_default(stringify!($field), || $default);
+                    )*
+                    Self {
+                        $(
+                            $field,
+                        )*
+                        $($(
+                            $lazy_field: None,
+                        )*)?
+                    }
+                }
+            }
+
+            impl Default for [<$fn_name:camel Params>] {
+                fn default() -> Self {
+                    let params = Self::__evaluate_defaults();
+                    let baseline = [<__ $fn_name:camel SpreadBaseline>] {
+                        $(
+                            $field: Some(params.$field.clone()),
+                        )*
+                    };
+                    [<__ $fn_name:upper _SPREAD_BASELINE>].with(|cell| *cell.borrow_mut() = Some(baseline));
+                    params
+                }
+            }
+
+            impl From<[<Create $fn_name:camel Builder>]> for [<$fn_name:camel Params>] {
+                fn from(builder: [<Create $fn_name:camel Builder>]) -> Self {
+                    let baseline = [<__ $fn_name:camel SpreadBaseline>] {
+                        $(
+                            $field: (!builder.__overridden.contains(stringify!($field)))
+                                .then(|| builder.__defaults.$field.clone()),
+                        )*
+                    };
+                    [<__ $fn_name:upper _SPREAD_BASELINE>].with(|cell| *cell.borrow_mut() = Some(baseline));
+                    Self {
+                        $(
+                            $field: builder.$field,
+                        )*
+                        $($(
+                            $lazy_field: builder.$lazy_field,
+                        )*)?
+                    }
+                }
+            }
+
+            impl From<[<$fn_name:camel Params>]> for [<Create $fn_name:camel Builder>] {
+                fn from(params: [<$fn_name:camel Params>]) -> Self {
+                    #[allow(unused_mut)]
+                    let mut builder = Self::new();
+                    $(
+                        builder = builder.$field(params.$field);
+                    )*
+                    $($(
+                        if let Some(value) = params.$lazy_field {
+                            builder = builder.$lazy_field(value);
+                        }
+                    )*)?
+                    builder
+                }
+            }
+
+            $(
+                $crate::__cfg_proptest! {
+                    impl $crate::__private::proptest::arbitrary::Arbitrary for [<$fn_name:camel Params>] {
+                        type Parameters = ();
+                        type Strategy = $crate::__private::proptest::strategy::BoxedStrategy<Self>;
+
+                        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                            use $crate::__private::proptest::strategy::Strategy;
+                            ($($arb_strategy,)*)
+                                .prop_map(|($($arb_field,)*)| {
+                                    let mut params = Self::from([<Create $fn_name:camel Builder>]::new());
+                                    $(
+                                        params.$arb_field = $arb_field;
+                                    )*
+                                    params
+                                })
+                                .boxed()
+                        }
+                    }
+                }
+            )?
+
+            /// Cria um registro usando todos os valores de params
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _from>](
+                db: &sea_orm::DatabaseConnection,
+                params: [<$fn_name:camel Params>],
+            ) -> Result<$model, sea_orm::DbErr> {
+                [<Create $fn_name:camel Builder>]::from(params).create(db).await
+            }
+
+            #[doc = concat!("Alias de `create_", stringify!($fn_name), "_from`")]
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _from_params>](
+                db: &sea_orm::DatabaseConnection,
+                params: [<$fn_name:camel Params>],
+            ) -> Result<$model, sea_orm::DbErr> {
+                [<create_ $fn_name _from>](db, params).await
+            }
+
+            $crate::define_factory!(
+                @create_all [$($public)?] [$($plural)?] [<$fn_name s>],
+                [<Create $fn_name:camel Builder>], $model
+            );
+
+            $(
+                [<__ $fn_name _factory_item>]! {
+                    /// Cria um registro já marcado como removido (soft delete)
+                    pub async fn [<create_ $fn_name _deleted>](
+                        db: &sea_orm::DatabaseConnection,
+                    ) -> Result<$model, sea_orm::DbErr> {
+                        let mut model = [<Create $fn_name:camel Builder>]::new().__resolve(db).await?;
+                        model.$soft_delete_field = sea_orm::ActiveValue::Set(Some($crate::datetime::Now::now()));
+                        let model = [<Create $fn_name:camel Builder>]::__insert(model, db).await?;
+                        [<Create $fn_name:camel Builder>]::__log(&model);
+                        Ok(model)
+                    }
+                }
+            )?
+
+            /// Marcador da factory para uso como `dyn DynFactory`
+            #[derive(Debug, Clone, Copy, Default)]
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub struct [<$fn_name:camel Factory>];
+
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            impl [<$fn_name:camel Factory>] {
+                /// Tags da cláusula `tags`
+                pub const TAGS: &'static [&'static str] = &[$($($tag),*)?];
+            }
+
+            impl $crate::DynFactory for [<$fn_name:camel Factory>] {
+                fn name(&self) -> &'static str {
+                    stringify!($fn_name)
+                }
+
+                fn tags(&self) -> &'static [&'static str] {
+                    Self::TAGS
+                }
+
+                fn create_default<'a>(
+                    &self,
+                    db: &'a sea_orm::DatabaseConnection,
+                ) -> $crate::__private::futures::future::BoxFuture<'a, Result<(), sea_orm::DbErr>> {
+                    ::std::boxed::Box::pin(async move { [<create_ $fn_name>](db).await.map(|_| ()) })
+                }
+            }
+
+            impl $crate::batch::BatchInsert for [<Create $fn_name:camel Builder>] {
+                type Model = $model;
+
+                fn parents(&self) -> Vec<::std::any::TypeId> {
+                    #[allow(unused_mut)]
+                    let mut parents = Vec::new();
+                    $($(
+                        if !self.__overridden.contains(stringify!($parent_fk)) && !self.__unset.contains(stringify!($parent_fk)) {
+                            parents.push(::std::any::TypeId::of::<<[<Create $parent_factory:camel Builder>] as $crate::batch::BatchInsert>::Model>());
+                        }
+                    )*)?
+                    parents
+                }
+
+                #[allow(unused_mut, unused_variables)]
+                fn adopt(mut self, parent: &dyn ::std::any::Any) -> Self {
+                    $($(
+                        if let Some(parent) = parent.downcast_ref::<<[<Create $parent_factory:camel Builder>] as $crate::batch::BatchInsert>::Model>() {
+                            self = $crate::BelongsTo::belongs_to(self, parent);
+                        }
+                    )*)?
+                    self
+                }
+
+                fn create(
+                    self,
+                    db: &sea_orm::DatabaseConnection,
+                ) -> $crate::__private::futures::future::BoxFuture<'_, Result<$model, sea_orm::DbErr>> {
+                    ::std::boxed::Box::pin(self.create(db))
+                }
+            }
+
+            /// Chamadas da factory como métodos da conexão, e.g. `db.create_specialty()`
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub trait [<$fn_name:camel FactoryExt>] {
+                /// Cria um registro com os valores padrão, como a função livre
+                fn [<create_ $fn_name>](&self) -> $crate::__private::futures::future::BoxFuture<'_, Result<$model, sea_orm::DbErr>>;
+
+                /// Cria `count` registros com os valores padrão, como a função livre
+                fn [<create_ $fn_name _list>](
+                    &self,
+                    count: usize,
+                ) -> $crate::__private::futures::future::BoxFuture<'_, Result<Vec<$model>, sea_orm::DbErr>>;
+            }
+
+            impl [<$fn_name:camel FactoryExt>] for sea_orm::DatabaseConnection {
+                fn [<create_ $fn_name>](&self) -> $crate::__private::futures::future::BoxFuture<'_, Result<$model, sea_orm::DbErr>> {
+                    ::std::boxed::Box::pin([<create_ $fn_name>](self))
+                }
+
+                fn [<create_ $fn_name _list>](
+                    &self,
+                    count: usize,
+                ) -> $crate::__private::futures::future::BoxFuture<'_, Result<Vec<$model>, sea_orm::DbErr>> {
+                    ::std::boxed::Box::pin([<create_ $fn_name _list>](self, count))
+                }
+            }
+
+            /// Cria `count` registros com os valores padrão
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list>](
+                db: &$crate::define_factory!(@conn_type [$($conn_accessor)?]),
+                count: usize,
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                let db = $crate::PrimaryConn::primary(db);
+                let mut models = Vec::with_capacity(count);
+                for _ in 0..count {
+                    models.push([<create_ $fn_name>](db).await?);
+                }
+                Ok(models)
+            }
+
+            /// Cria `count` registros e retorna apenas os ids
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list_ids>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+            ) -> Result<Vec<$crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?)>, sea_orm::DbErr> {
+                let mut ids = Vec::with_capacity(count);
+                for _ in 0..count {
+                    ids.push([<Create $fn_name:camel Builder>]::new().create_returning_id(db).await?);
+                }
+                Ok(ids)
+            }
+
+            /// Cria `count` registros em um único INSERT e retorna apenas os ids
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _bulk_ids>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+            ) -> Result<Vec<$crate::define_factory!(@id_type [$($id_newtype)?] $($pk_type $(, $pk_wrap)?)?)>, sea_orm::DbErr> {
+                type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                if count == 0 {
+                    return Ok(Vec::new());
+                }
+                let mut models = Vec::with_capacity(count);
+                for _ in 0..count {
+                    models.push([<Create $fn_name:camel Builder>]::new().__resolve(db).await?);
+                }
+                let insert = <Entity as sea_orm::EntityTrait>::insert_many(models);
+                let ids = if sea_orm::ConnectionTrait::support_returning(db) {
+                    insert.exec_with_returning_keys(db).await?
+                } else {
+                    #[allow(unused_imports)]
+                    use $crate::bulk::{ContiguousIds as _, UnknownIds as _};
+                    let last = insert.exec(db).await?.last_insert_id;
+                    (&$crate::bulk::LastInsertId(last, count)).ids()?
+                };
+                Ok(ids.into_iter().map(::core::convert::Into::into).collect())
+            }
+
+            /// Cria `count` registros resolvendo os campos lazy uma única vez
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list_sharing_parents>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                let mut models = Vec::with_capacity(count);
+                if count == 0 {
+                    return Ok(models);
+                }
+                let first = [<create_ $fn_name>](db).await?;
+                for _ in 1..count {
+                    let builder = [<Create $fn_name:camel Builder>]::new()
+                        $($(.$lazy_field(::core::clone::Clone::clone(&first.$lazy_field)))*)?;
+                    models.push(builder.create(db).await?);
+                }
+                models.insert(0, first);
+                Ok(models)
+            }
+
+            /// Cria `count` registros em paralelo, com até `concurrency` inserts simultâneos
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list_concurrent>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+                concurrency: usize,
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                use $crate::__private::futures::StreamExt as _;
+                let results: Vec<Result<$model, sea_orm::DbErr>> =
+                    $crate::__private::futures::stream::iter((0..count).map(|_| [<create_ $fn_name>](db)))
+                        .buffer_unordered(concurrency.max(1))
+                        .collect()
+                        .await;
+                results.into_iter().collect()
+            }
+
+            /// Cria `count` registros customizando cada builder pelo índice
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list_with>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+                f: impl Fn(usize, [<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>],
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                [<create_ $fn_name _list_from>](db, 0, count, f).await
+            }
+
+            /// Cria um registro por chave, customizado por `f`, e os retorna indexados pela chave
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _map_by>]<K>(
+                db: &sea_orm::DatabaseConnection,
+                keys: &[K],
+                f: impl Fn(&K, [<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>],
+            ) -> Result<::std::collections::HashMap<K, $model>, sea_orm::DbErr>
+            where
+                K: ::std::hash::Hash + Eq + Clone,
+            {
+                let mut models = ::std::collections::HashMap::with_capacity(keys.len());
+                for key in keys {
+                    let model = f(key, [<Create $fn_name:camel Builder>]::new()).create(db).await?;
+                    models.insert(key.clone(), model);
+                }
+                Ok(models)
+            }
+
+            /// Constrói `count` ActiveModels sem salvar, customizando cada builder pelo índice
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<build_ $fn_name _list_with>](
+                count: usize,
+                f: impl Fn(usize, [<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>],
+            ) -> Vec<$active_model> {
+                (0..count)
+                    .map(|index| f(index, [<Create $fn_name:camel Builder>]::new()).build())
+                    .collect()
+            }
+
+            /// Cria `count` registros com índices a partir de `start`
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<create_ $fn_name _list_from>](
+                db: &sea_orm::DatabaseConnection,
+                start: usize,
+                count: usize,
+                f: impl Fn(usize, [<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>],
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                let mut models = Vec::with_capacity(count);
+                for index in start..start + count {
+                    models.push(f(index, [<Create $fn_name:camel Builder>]::new()).create(db).await?);
+                }
+                Ok(models)
+            }
+
+            /// Insere um registro para cada fixture, na ordem dada
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<seed_ $fn_name _fixtures>](
+                db: &sea_orm::DatabaseConnection,
+                fixtures: &[fn([<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>]],
+            ) -> Result<Vec<$model>, sea_orm::DbErr> {
+                let mut models = Vec::with_capacity(fixtures.len());
+                for fixture in fixtures {
+                    models.push(fixture([<Create $fn_name:camel Builder>]::new()).create(db).await?);
+                }
+                Ok(models)
+            }
+
+            /// Cria registros sob demanda conforme o stream é consumido
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub fn [<create_ $fn_name _stream>](
+                db: &sea_orm::DatabaseConnection,
+                count: usize,
+            ) -> impl $crate::__private::futures::Stream<Item = Result<$model, sea_orm::DbErr>> + '_ {
+                $crate::__private::futures::stream::unfold(0, move |created| async move {
+                    if created >= count {
+                        None
+                    } else {
+                        Some(([<create_ $fn_name>](db).await, created + 1))
+                    }
+                })
+            }
+
+            /// Atualiza um registro existente aplicando apenas os campos definidos no builder
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<update_ $fn_name>](
+                db: &sea_orm::DatabaseConnection,
+                id: impl Into<
+                    <<<$model as sea_orm::ModelTrait>::Entity as sea_orm::EntityTrait>::PrimaryKey as sea_orm::PrimaryKeyTrait>::ValueType,
+                >,
+                f: impl FnOnce([<Create $fn_name:camel Builder>]) -> [<Create $fn_name:camel Builder>],
+            ) -> Result<$model, sea_orm::DbErr> {
+                type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                let model = <Entity as sea_orm::EntityTrait>::find_by_id(id)
+                    .one(db)
+                    .await?
+                    .ok_or_else(|| {
+                        sea_orm::DbErr::RecordNotFound(concat!(stringify!($fn_name), " not found").to_string())
+                    })?;
+                let builder = f([<Create $fn_name:camel Builder>]::__from_model(&model));
+                let mut active: $active_model = sea_orm::IntoActiveModel::into_active_model(model);
+                $(
+                    if builder.__overridden.contains(stringify!($field)) {
+                        active.$field = sea_orm::ActiveValue::Set(builder.$field);
+                    }
+                )*
+                $($(
+                    if let Some(value) = builder.$lazy_field {
+                        active.$lazy_field = sea_orm::ActiveValue::Set(value);
+                    }
+                )*)?
+                $crate::define_factory!(@transform active $(, $($transform_field: $transform_fn),*)?);
+                sea_orm::ActiveModelTrait::update(active, db).await
+            }
+
+            /// Compara dois models ignorando as colunas dadas, com panic listando as diferenças
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            #[track_caller]
+            pub fn [<assert_ $fn_name _eq_ignoring>](left: &$model, right: &$model, ignore: &[&str]) {
+                let diff = $crate::assertions::model_diff(left, right, ignore);
+                assert!(
+                    diff.is_empty(),
+                    "`{}` models differ (ignoring {:?}):\n{}",
+                    stringify!($fn_name),
+                    ignore,
+                    diff.join("\n")
+                );
+            }
+
+            /// Verifica a quantidade de registros da tabela, com panic nomeando a tabela
+            #[cfg_attr(not(any($($public)?)), allow(dead_code))]
+            pub async fn [<assert_ $fn_name _count>](db: &sea_orm::DatabaseConnection, expected: u64) {
+                type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                let entity = Entity::default();
+                let table = sea_orm::EntityName::table_name(&entity);
+                let actual = sea_orm::PaginatorTrait::count(<Entity as sea_orm::EntityTrait>::find(), db)
+                    .await
+                    .unwrap_or_else(|err| panic!("failed to count rows of `{}`: {}", table, err));
+                assert_eq!(actual, expected, "table `{}` has {} rows, expected {}", table, actual, expected);
+            }
+
+            $($(
+                [<__ $fn_name _factory_item>]! {
+                    #[doc = concat!("Busca um registro pelo campo único `", stringify!($unique_field), "`")]
+                    pub async fn [<find_ $fn_name _by_ $unique_field>](
+                        db: &sea_orm::DatabaseConnection,
+                        value: impl Into<sea_orm::Value>,
+                    ) -> Result<Option<$model>, sea_orm::DbErr> {
+                        type Entity = <$model as sea_orm::ModelTrait>::Entity;
+                        type Column = <Entity as sea_orm::EntityTrait>::Column;
+                        sea_orm::QueryFilter::filter(
+                            <Entity as sea_orm::EntityTrait>::find(),
+                            sea_orm::ColumnTrait::eq(&Column::[<$unique_field:camel>], value),
+                        )
+                        .one(db)
+                        .await
+                    }
+                }
+
+                [<__ $fn_name _factory_item>]! {
+                    #[doc = concat!("Busca um registro pelo campo único `", stringify!($unique_field), "` ou o cria com esse valor; o bool indica se foi criado")]
+                    pub async fn [<get_or_create_ $fn_name _by_ $unique_field>](
+                        db: &sea_orm::DatabaseConnection,
+                        key: impl Into<[<__ $fn_name:camel $unique_field:camel Type>]>,
+                    ) -> Result<($model, bool), sea_orm::DbErr> {
+                        let key = key.into();
+                        match [<find_ $fn_name _by_ $unique_field>](db, ::core::clone::Clone::clone(&key)).await? {
+                            Some(existing) => Ok((existing, false)),
+                            None => {
+                                let created = [<Create $fn_name:camel Builder>]::new().$unique_field(key).create(db).await?;
+                                Ok((created, true))
+                            }
+                        }
+                    }
+                }
+            )*)?
+        }
+    };
+}
+
+// ============================================
+// DEFININDO AS FACTORIES
+// ============================================
 
 #[cfg(test)]
 mod factory_tests {
-    use super::*;
     use chrono::Datelike;
     use sea_orm::{
         ActiveModelTrait, ActiveValue, Database, DatabaseConnection, Schema, entity::prelude::*,
     };
     use uuid::Uuid;
 
-    pub mod specialties {
+    use crate::create_schema;
+
+    pub mod specialties {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "specialties")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub uuid: Uuid,
+            pub name: String,
+            pub description: Option<String>,
+            pub is_active: bool,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod doctors {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "doctors")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub uuid: Uuid,
+            pub first_name: String,
+            pub last_name: String,
+            pub email: String,
+            pub specialty_id: i32,
+            pub license_number: String,
+            pub phone: Option<String>,
+            pub is_active: bool,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {
+            #[sea_orm(
+                belongs_to = "super::specialties::Entity",
+                from = "Column::SpecialtyId",
+                to = "super::specialties::Column::Id"
+            )]
+            Specialty,
+        }
+
+        impl Related<super::specialties::Entity> for Entity {
+            fn to() -> RelationDef {
+                Relation::Specialty.def()
+            }
+        }
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod patients {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "patients")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub uuid: Uuid,
+            pub first_name: String,
+            pub last_name: String,
+            pub email: String,
+            pub date_of_birth: chrono::NaiveDate,
+            pub phone: String,
+            pub address: Option<String>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod settings {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "settings")]
+        pub struct Model {
+            #[sea_orm(primary_key, auto_increment = false)]
+            pub id: i32,
+            #[sea_orm(unique)]
+            pub key: String,
+            pub value: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    /// Entidade no formato expandido, com um `ActiveModel` escrito à mão e sem `Default`
+    pub mod stamps {
+        use super::*;
+        use sea_orm::{IntoActiveModel, TryIntoModel};
+
+        #[derive(Copy, Clone, Default, Debug, DeriveEntity)]
+        pub struct Entity;
+
+        impl EntityName for Entity {
+            fn table_name(&self) -> &str {
+                "stamps"
+            }
+        }
+
+        #[derive(Clone, Debug, PartialEq, Eq, DeriveModel)]
+        pub struct Model {
+            pub id: i32,
+            pub code: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveColumn)]
+        pub enum Column {
+            Id,
+            Code,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DerivePrimaryKey)]
+        pub enum PrimaryKey {
+            Id,
+        }
+
+        impl PrimaryKeyTrait for PrimaryKey {
+            type ValueType = i32;
+
+            fn auto_increment() -> bool {
+                true
+            }
+        }
+
+        impl ColumnTrait for Column {
+            type EntityName = Entity;
+
+            fn def(&self) -> ColumnDef {
+                match self {
+                    Self::Id => ColumnType::Integer.def(),
+                    Self::Code => ColumnType::String(StringLen::None).def(),
+                }
+            }
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter)]
+        pub enum Relation {}
+
+        impl RelationTrait for Relation {
+            fn def(&self) -> RelationDef {
+                match *self {}
+            }
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct ActiveModel {
+            pub id: ActiveValue<i32>,
+            pub code: ActiveValue<String>,
+        }
+
+        impl From<Model> for ActiveModel {
+            fn from(model: Model) -> Self {
+                Self {
+                    id: ActiveValue::Unchanged(model.id),
+                    code: ActiveValue::Unchanged(model.code),
+                }
+            }
+        }
+
+        impl IntoActiveModel<ActiveModel> for Model {
+            fn into_active_model(self) -> ActiveModel {
+                self.into()
+            }
+        }
+
+        impl TryIntoModel<Model> for ActiveModel {
+            fn try_into_model(self) -> Result<Model, DbErr> {
+                match (self.id, self.code) {
+                    (
+                        ActiveValue::Set(id) | ActiveValue::Unchanged(id),
+                        ActiveValue::Set(code) | ActiveValue::Unchanged(code),
+                    ) => Ok(Model { id, code }),
+                    _ => Err(DbErr::AttrNotSet("stamps".to_string())),
+                }
+            }
+        }
+
+        impl ActiveModelTrait for ActiveModel {
+            type Entity = Entity;
+
+            fn take(&mut self, c: Column) -> ActiveValue<Value> {
+                match c {
+                    Column::Id => std::mem::take(&mut self.id).into_wrapped_value(),
+                    Column::Code => std::mem::take(&mut self.code).into_wrapped_value(),
+                }
+            }
+
+            fn get(&self, c: Column) -> ActiveValue<Value> {
+                match c {
+                    Column::Id => self.id.clone().into_wrapped_value(),
+                    Column::Code => self.code.clone().into_wrapped_value(),
+                }
+            }
+
+            fn try_set(&mut self, c: Column, v: Value) -> Result<(), DbErr> {
+                use sea_orm::sea_query::ValueType;
+                let type_err = |e: sea_orm::sea_query::ValueTypeErr| DbErr::Type(e.to_string());
+                match c {
+                    Column::Id => {
+                        self.id =
+                            ActiveValue::Set(<i32 as ValueType>::try_from(v).map_err(type_err)?)
+                    }
+                    Column::Code => {
+                        self.code =
+                            ActiveValue::Set(<String as ValueType>::try_from(v).map_err(type_err)?)
+                    }
+                }
+                Ok(())
+            }
+
+            fn not_set(&mut self, c: Column) {
+                match c {
+                    Column::Id => self.id = ActiveValue::NotSet,
+                    Column::Code => self.code = ActiveValue::NotSet,
+                }
+            }
+
+            fn is_not_set(&self, c: Column) -> bool {
+                match c {
+                    Column::Id => self.id.is_not_set(),
+                    Column::Code => self.code.is_not_set(),
+                }
+            }
+
+            fn default() -> Self {
+                Self {
+                    id: ActiveValue::NotSet,
+                    code: ActiveValue::NotSet,
+                }
+            }
+
+            fn default_values() -> Self {
+                <Self as ActiveModelTrait>::default()
+            }
+
+            fn reset(&mut self, c: Column) {
+                match c {
+                    Column::Id => self.id.reset(),
+                    Column::Code => self.code.reset(),
+                }
+            }
+        }
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod tickets {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "tickets")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            #[sea_orm(default_value = "open")]
+            pub status: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod labels {
+        use super::*;
+        use crate::Factory;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Factory)]
+        #[sea_orm(table_name = "labels")]
+        #[factory(name = "label")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            #[factory(default = "Urgent".to_string())]
+            pub name: String,
+            #[factory(default = format!("#{:06x}", 0xff0000))]
+            pub color: String,
+            #[factory(lazy = super::get_specialty_id)]
+            pub specialty_id: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod appointments {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "appointments")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub patient_name: String,
+            pub scheduled_at: DateTimeWithTimeZone,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod posts {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "posts")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            pub deleted_at: Option<DateTimeUtc>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod members {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "members")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            #[sea_orm(default_value = false)]
+            pub is_active: bool,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    // Tabela com coluna array, suportada apenas no Postgres (não é criada no setup SQLite)
+    pub mod profiles {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "loco_factory_profiles")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            pub tags: Vec<String>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod accounts {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "accounts")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            pub region: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod badges {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "badges")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            #[sea_orm(column_name = "badge_code", unique)]
+            pub code: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod notes {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "notes")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub title: String,
+            pub slug: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        #[async_trait::async_trait]
+        impl ActiveModelBehavior for ActiveModel {
+            async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+            where
+                C: ConnectionTrait,
+            {
+                if insert {
+                    let slug = self.title.as_ref().to_lowercase().replace(' ', "-");
+                    self.slug = ActiveValue::Set(slug);
+                }
+                Ok(self)
+            }
+        }
+    }
+
+    pub mod dossiers {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "dossiers")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub metadata: Json,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod tokens {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "tokens")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub label: String,
+            pub secret: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {
+            fn new() -> Self {
+                Self {
+                    secret: ActiveValue::Set("seeded-by-new".to_string()),
+                    ..ActiveModelTrait::default()
+                }
+            }
+        }
+    }
+
+    pub mod comments {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "comments")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub body: String,
+            pub commentable_type: String,
+            pub commentable_id: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod invoices {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "invoices")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub amount: Decimal,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod referrals {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "referrals")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub reason: String,
+            pub specialty_id: i32,
+            pub patient_id: Option<i32>,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod departments {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "departments")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod wards {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "wards")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            pub department_id: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod beds {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "beds")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub label: String,
+            pub ward_id: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod floors {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "floors")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub number: i32,
+            pub label: String,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    pub mod rooms {
+        use super::*;
+
+        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+        #[sea_orm(table_name = "rooms")]
+        pub struct Model {
+            #[sea_orm(primary_key)]
+            pub id: i32,
+            pub name: String,
+            pub floor_number: i32,
+        }
+
+        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+        pub enum Relation {}
+
+        impl ActiveModelBehavior for ActiveModel {}
+    }
+
+    /// Setup de banco em memória SQLite
+    async fn setup_test_db() -> DatabaseConnection {
+        let db = Database::connect("sqlite::memory:")
+            .await
+            .expect("Failed to connect to test database");
+
+        create_schema!(
+            db;
+            specialties::Entity,
+            doctors::Entity,
+            patients::Entity,
+            settings::Entity,
+            tickets::Entity,
+            labels::Entity,
+            appointments::Entity,
+            posts::Entity,
+            members::Entity,
+            accounts::Entity,
+            badges::Entity,
+            notes::Entity,
+            dossiers::Entity,
+            tokens::Entity,
+            comments::Entity,
+            invoices::Entity,
+            referrals::Entity,
+            departments::Entity,
+            wards::Entity,
+            beds::Entity,
+            stamps::Entity,
+            floors::Entity,
+            rooms::Entity,
+        )
+        .await
+        .expect("Failed to create test tables")
+    }
+
+    async fn count_specialties(db: &DatabaseConnection) -> Result<u64, DbErr> {
+        use sea_orm::EntityTrait;
+        specialties::Entity::find().count(db).await
+    }
+
+    async fn count_doctors(db: &DatabaseConnection) -> Result<u64, DbErr> {
+        use sea_orm::EntityTrait;
+        doctors::Entity::find().count(db).await
+    }
+
+    async fn count_patients(db: &DatabaseConnection) -> Result<u64, DbErr> {
+        use sea_orm::EntityTrait;
+        patients::Entity::find().count(db).await
+    }
+
+    async fn find_specialty_by_id(
+        db: &DatabaseConnection,
+        id: i32,
+    ) -> Result<Option<specialties::Model>, DbErr> {
+        use sea_orm::EntityTrait;
+        specialties::Entity::find_by_id(id).one(db).await
+    }
+
+    define_factory! {
+        /// Cria uma specialty de teste
+        specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                /// Nome exibido da specialty
+                name: String = "Test Specialty".to_string(),
+                description: Option<String> = Some("Test Description".to_string()),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            unique: [uuid, name],
+            arbitrary: {
+                name: "[A-Za-z ]{1,20}",
+                is_active: proptest::prelude::any::<bool>(),
+            },
+            plural: specialties,
+            label_field: name,
+            tags: ["core", "demo"],
+            validate: {
+                name: |name| !name.is_empty() => "must not be empty",
+                name: |name| name.len() <= 40 => "must have at most 40 characters",
+                description: |description| description.is_some() => "is required",
+            },
+            nullable: [description],
+            unique_suffix: name,
+            has_many: [doctors::Column::SpecialtyId]
+        }
+    }
+
+    // async fn get_specialty_id(db: &DatabaseConnection) -> Result<i32, DbErr> {
+    //    create_specialty(db).await.map(|s| s.id)
+    // }
+
+    define_factory! {
+        /// Cria um doctor de teste
+        doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "John".to_string(),
+                last_name: String = "Doe".to_string(),
+                email: String = format!("doctor_{}@example.com", Uuid::new_v4()),
+                license_number: String = format!("LIC{}", Uuid::new_v4().to_string()[..8].to_uppercase()),
+                uuid: Uuid = Uuid::new_v4(),
+                phone: Option<String> = Some("+5511999999999".to_string()),
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                #[doc = "Specialty do doctor, criada sob demanda"]
+                specialty_id: i32 = get_specialty_id,
+            },
+            placeholder: { specialty_id: 0 },
+            tags: ["demo"],
+            related: { specialty: specialties::Entity },
+            belongs_to: { specialty_id: specialty },
+            preflight_unique: [uuid, email]
+        }
+    }
+
+    define_factory! {
+        /// Cria um patient de teste
+        patient => patients::Model {
+            active_model: patients::ActiveModel,
+            fields: {
+                first_name: String = "Maria".to_string(),
+                last_name: String = "Silva".to_string(),
+                email: String = format!("patient_{}@example.com", Uuid::new_v4()),
+                date_of_birth: chrono::NaiveDate = chrono::NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
+                phone: String = "+5511988888888".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                address: Option<String> = Some("Rua Teste, 123".to_string()),
+            }
+        }
+    }
+
+    async fn insert_encrypted_specialty<C: sea_orm::ConnectionTrait>(
+        mut model: specialties::ActiveModel,
+        db: &C,
+    ) -> Result<specialties::Model, DbErr> {
+        model.description = ActiveValue::Set(Some("encrypted".to_string()));
+        model.insert(db).await
+    }
+
+    define_factory! {
+        /// Cria uma specialty usando uma rotina de insert customizada
+        encrypted_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Encrypted Specialty".to_string(),
+                description: Option<String> = None,
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            insert_with: insert_encrypted_specialty
+        }
+    }
+
+    fn next_setting_id() -> i32 {
+        static NEXT_ID: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(1);
+        NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    define_factory! {
+        /// Cria uma setting de teste listando todas as colunas
+        setting => settings::Model {
+            active_model: settings::ActiveModel,
+            fields: {
+                id: i32 = next_setting_id(),
+                key: String = format!("setting_{}", Uuid::new_v4()),
+                value: String = "enabled".to_string(),
+            },
+            complete: true,
+            primary_key: { id: i32, explicit: true }
+        }
+    }
+
+    define_factory! {
+        /// Cria um stamp, cujo `ActiveModel` não implementa `Default`
+        stamp => stamps::Model {
+            active_model: stamps::ActiveModel,
+            fields: {
+                id: i32 = 0,
+                code: String = format!("STP-{}", Uuid::new_v4().simple()),
+            },
+            complete: true
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty com descrição dependente do backend
+        backend_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Backend Specialty".to_string(),
+                description: Option<String> = crate::backend_default!(
+                    sqlite: Some("sqlite".to_string()),
+                    postgres: Some("postgres".to_string()),
+                ),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct SpecialtyId(pub i32);
+
+    impl From<i32> for SpecialtyId {
+        fn from(id: i32) -> Self {
+            SpecialtyId(id)
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty cujo id é retornado como newtype
+        typed_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Typed Specialty".to_string(),
+                description: Option<String> = None,
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            primary_key: { id: i32, wrap: SpecialtyId }
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cujo license_number deriva do last_name
+        licensed_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "John".to_string(),
+                last_name: String = "Doe".to_string(),
+                email: String = format!("doctor_{}@example.com", Uuid::new_v4()),
+                uuid: Uuid = Uuid::new_v4(),
+                phone: Option<String> = None,
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+                license_number: String => |_db, partial| async move {
+                    Ok(format!("LIC-{}", partial.last_name.as_ref().to_uppercase()))
+                },
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cujo license_number deriva do specialty_id resolvido antes
+        chained_lazy_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "John".to_string(),
+                last_name: String = "Doe".to_string(),
+                email: String = format!("doctor_{}@example.com", Uuid::new_v4()),
+                uuid: Uuid = Uuid::new_v4(),
+                phone: Option<String> = None,
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+                license_number: String => |_db, partial| async move {
+                    Ok(format!("LIC-{}", partial.specialty_id.as_ref()))
+                },
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cujo email deriva do first_name e do last_name
+        named_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "Jane".to_string(),
+                last_name: String = "Roe".to_string(),
+                email: String = format!("{}.{}@example.com", first_name, last_name).to_lowercase(),
+                license_number: String = format!("LIC-{}", last_name.to_uppercase()),
+                uuid: Uuid = Uuid::new_v4(),
+                phone: Option<String> = None,
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um appointment agendado no fuso de São Paulo
+        appointment => appointments::Model {
+            active_model: appointments::ActiveModel,
+            fields: {
+                patient_name: String = "Maria Silva".to_string(),
+                scheduled_at: DateTimeWithTimeZone = crate::datetime::now_in("America/Sao_Paulo"),
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um post de teste com soft delete
+        post => posts::Model {
+            active_model: posts::ActiveModel,
+            fields: {
+                title: String = "Hello".to_string(),
+            },
+            soft_delete: deleted_at
+        }
+    }
+
+    define_factory! {
+        /// Cria um member ativo; a coluna is_active tem default false no banco
+        member => members::Model {
+            active_model: members::ActiveModel,
+            fields: {
+                name: String = "Member".to_string(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cujo email é sempre salvo em minúsculas
+        normalized_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "John".to_string(),
+                last_name: String = "Doe".to_string(),
+                email: String = format!("Doctor_{}@Example.com", Uuid::new_v4()),
+                license_number: String = "LIC-NORMALIZED".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                phone: Option<String> = None,
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+            },
+            transform: {
+                email: |v| v.to_lowercase(),
+                license_number: |v| v.replace('-', "_"),
+            },
+            preflight_unique: [email]
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub struct SpecialtySummary {
+        pub id: i32,
+        pub label: String,
+    }
+
+    define_factory! {
+        /// Cria uma specialty e devolve apenas um resumo dela
+        summary_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Summary".to_string(),
+                description: Option<String> = None,
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            returns: SpecialtySummary,
+            map: |inserted| SpecialtySummary {
+                id: inserted.id,
+                label: inserted.name.to_uppercase(),
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty cujo builder recalcula os defaults a cada build
+        fresh_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Fresh".to_string(),
+                description: Option<String> = None,
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            lazy_defaults: true
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor administrador a partir da factory doctor
+        admin_doctor => doctors::Model {
+            extends: doctor,
+            fields: {
+                email: format!("admin_{}@hospital.com", Uuid::new_v4()),
+                is_active: true,
+                phone: None,
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty sem descrição
+        plain_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Plain Specialty".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            plural: plain_specialties
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty com descrição a partir da factory plain_specialty
+        annotated_specialty => specialties::Model {
+            extends: plain_specialty,
+            fields: {
+                name: "Annotated Specialty".to_string(),
+            },
+            additions: {
+                description: Option<String> = Some(format!("About {}", name)),
+            }
+        }
+    }
+
+    #[expect(
+        dead_code,
+        reason = "public mode leaves unused generated items to dead-code analysis"
+    )]
+    mod public_extension {
+        use super::*;
+
+        define_factory! {
+            /// Cria uma specialty pública a partir da factory plain_specialty
+            public_plain_specialty => specialties::Model {
+                extends: plain_specialty,
+                fields: {},
+                public: true
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria um profile com tags (coluna array do Postgres)
+        profile => profiles::Model {
+            active_model: profiles::ActiveModel,
+            fields: {
+                name: String = "Profile".to_string(),
+                tags: Vec<String> = vec!["general".into()],
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria uma account no banco da sua região
+        account => accounts::Model {
+            active_model: accounts::ActiveModel,
+            fields: {
+                name: String = "Account".to_string(),
+                region: String = "us".to_string(),
+            },
+            route: |account, dbs| match account.region.as_ref().as_str() {
+                "eu" => &dbs[1],
+                _ => &dbs[0],
+            }
+        }
+    }
+
+    static RETRY_KEY: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(1);
+
+    fn next_retry_key() -> String {
+        let key = RETRY_KEY.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        format!("retry-{}", key)
+    }
+
+    define_factory! {
+        /// Cria uma setting com chave sequencial, tentando novamente em conflitos
+        retrying_setting => settings::Model {
+            active_model: settings::ActiveModel,
+            fields: {
+                id: i32 = next_setting_id(),
+                key: String = next_retry_key(),
+                value: String = "enabled".to_string(),
+            },
+            complete: true,
+            primary_key: { id: i32, explicit: true },
+            retry_on_conflict: 3
+        }
+    }
+
+    define_factory! {
+        /// Cria um ticket deixando as colunas calculadas pelo banco como NotSet
+        ticket => tickets::Model {
+            active_model: tickets::ActiveModel,
+            fields: {
+                title: String = "Broken monitor".to_string(),
+            },
+            complete: true,
+            generated: { id, status }
+        }
+    }
+
+    mod specialties_factory_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_specialty_with_defaults() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            assert_eq!(specialty.name, "Test Specialty");
+            assert_eq!(specialty.description, Some("Test Description".to_string()));
+            assert!(specialty.id > 0);
+            assert!(specialty.is_active);
+        }
+
+        #[tokio::test]
+        async fn test_create_multiple_specialties() {
+            let db = setup_test_db().await;
+
+            let specialty1 = create_specialty(&db).await.unwrap();
+            let specialty2 = create_specialty(&db).await.unwrap();
+            let specialty3 = create_specialty(&db).await.unwrap();
+
+            assert_ne!(specialty1.id, specialty2.id);
+            assert_ne!(specialty2.id, specialty3.id);
+            assert_ne!(specialty1.uuid, specialty2.uuid);
+
+            let count = count_specialties(&db).await.unwrap();
+            assert_eq!(count, 3);
+        }
+
+        #[tokio::test]
+        async fn test_specialty_uuid_is_unique() {
+            let db = setup_test_db().await;
+
+            let specialty1 = create_specialty(&db).await.unwrap();
+            let specialty2 = create_specialty(&db).await.unwrap();
+
+            assert_ne!(specialty1.uuid, specialty2.uuid);
+        }
+
+        #[tokio::test]
+        async fn test_auto_increment_id() {
+            let db = setup_test_db().await;
+
+            let specialty1 = create_specialty(&db).await.unwrap();
+            let specialty2 = create_specialty(&db).await.unwrap();
+            let specialty3 = create_specialty(&db).await.unwrap();
+
+            assert_eq!(specialty1.id + 1, specialty2.id);
+            assert_eq!(specialty2.id + 1, specialty3.id);
+        }
+
+        #[tokio::test]
+        async fn test_specialty_persisted_correctly() {
+            let db = setup_test_db().await;
+
+            let created = create_specialty(&db).await.unwrap();
+            let found = find_specialty_by_id(&db, created.id).await.unwrap();
+
+            assert!(found.is_some());
+            let found = found.unwrap();
+            assert_eq!(found.id, created.id);
+            assert_eq!(found.name, created.name);
+            assert_eq!(found.uuid, created.uuid);
+        }
+    }
+
+    // ============================================
+    // TESTES - BUILDER PATTERN
+    // ============================================
+
+    mod builder_factory_tests {
+        use super::*;
+        #[tokio::test]
+        async fn test_builder_with_default_values() {
+            let db = setup_test_db().await;
+            let specialty = CreateSpecialtyBuilder::new().create(&db).await.unwrap();
+
+            assert_eq!(specialty.name, "Test Specialty");
+            assert_eq!(specialty.description, Some("Test Description".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_builder_with_custom_name() {
+            let db = setup_test_db().await;
+
+            let specialty = CreateSpecialtyBuilder::new()
+                .name("Cardiology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Cardiology");
+        }
+
+        #[tokio::test]
+        async fn test_builder_with_multiple_overrides() {
+            let db = setup_test_db().await;
+
+            let custom_uuid = Uuid::new_v4();
+            let specialty = CreateSpecialtyBuilder::new()
+                .name("Neurology".to_string())
+                .description(Some("Brain specialist".to_string()))
+                .uuid(custom_uuid)
+                .is_active(false)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Neurology");
+            assert_eq!(specialty.description, Some("Brain specialist".to_string()));
+            assert_eq!(specialty.uuid, custom_uuid);
+            assert!(!specialty.is_active);
+        }
+
+        #[tokio::test]
+        async fn test_builder_with_none_description() {
+            let db = setup_test_db().await;
+
+            let specialty = CreateSpecialtyBuilder::new()
+                .name("Surgery".to_string())
+                .description(None)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Surgery");
+            assert_eq!(specialty.description, None);
+        }
+
+        #[tokio::test]
+        async fn test_builder_helper_function() {
+            let db = setup_test_db().await;
+
+            let specialty = create_specialty_builder()
+                .name("Oncology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Oncology");
+        }
+
+        #[tokio::test]
+        async fn test_builder_reuse() {
+            let db = setup_test_db().await;
+
+            let base_builder = CreateSpecialtyBuilder::new().name("Base Specialty".to_string());
+
+            let specialty1 = base_builder.clone().create(&db).await.unwrap();
+            let specialty2 = base_builder.create(&db).await.unwrap();
+
+            assert_eq!(specialty1.name, "Base Specialty");
+            assert_eq!(specialty2.name, "Base Specialty");
+            assert_ne!(specialty1.id, specialty2.id);
+        }
+
+        #[test]
+        fn test_builder_requires_db() {
+            let builder = create_doctor_builder();
+            assert!(builder.requires_db());
+
+            let builder = builder.specialty_id(1);
+            assert!(!builder.requires_db());
+
+            assert!(!create_specialty_builder().requires_db());
+        }
+
+        #[tokio::test]
+        async fn test_builder_mut_setters() {
+            let db = setup_test_db().await;
+
+            for (i, inactive) in [false, true].into_iter().enumerate() {
+                let mut builder = create_specialty_builder();
+                builder.set_name(format!("Ward {}", i));
+                if inactive {
+                    builder.set_is_active(false).set_description(None);
+                }
+                let specialty = builder.create(&db).await.unwrap();
+
+                assert_eq!(specialty.name, format!("Ward {}", i));
+                assert_eq!(specialty.is_active, !inactive);
+                assert_eq!(specialty.description.is_none(), inactive);
+            }
+        }
+
+        #[tokio::test]
+        async fn test_builder_mut_setter_for_lazy_field() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let mut builder = create_doctor_builder();
+            builder.set_specialty_id(specialty.id);
+            let doctor = builder.create(&db).await.unwrap();
+
+            assert_eq!(doctor.specialty_id, specialty.id);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_builder_create_returning_id() {
+            let db = setup_test_db().await;
+
+            let id = create_specialty_builder()
+                .name("Dermatology".to_string())
+                .create_returning_id(&db)
+                .await
+                .unwrap();
+
+            assert!(id > 0);
+            let found = find_specialty_by_id(&db, id).await.unwrap().unwrap();
+            assert_eq!(found.name, "Dermatology");
+        }
+
+        #[test]
+        fn test_build_returns_active_model() {
+            let active_model = CreateSpecialtyBuilder::new()
+                .name("Test".to_string())
+                .build();
+
+            assert!(matches!(active_model.name, ActiveValue::Set(_)));
+            if let ActiveValue::Set(name) = active_model.name {
+                assert_eq!(name, "Test");
+            }
+        }
+
+        #[test]
+        fn test_build_does_not_require_database() {
+            let active_model = CreateSpecialtyBuilder::new()
+                .name("No DB Test".to_string())
+                .description(Some("Works without database".to_string()))
+                .build();
+
+            if let ActiveValue::Set(name) = active_model.name {
+                assert_eq!(name, "No DB Test");
+            }
+        }
+
+        #[test]
+        fn test_build_with_custom_uuid() {
+            let custom_uuid = Uuid::new_v4();
+            let active_model = CreateSpecialtyBuilder::new().uuid(custom_uuid).build();
+
+            if let ActiveValue::Set(uuid) = active_model.uuid {
+                assert_eq!(uuid, custom_uuid);
+            }
+        }
+
+        #[test]
+        fn test_build_all_fields_set() {
+            let active_model = CreateSpecialtyBuilder::new()
+                .name("Complete".to_string())
+                .description(Some("Full description".to_string()))
+                .uuid(Uuid::new_v4())
+                .is_active(false)
+                .build();
+
+            assert!(matches!(active_model.name, ActiveValue::Set(_)));
+            assert!(matches!(active_model.description, ActiveValue::Set(_)));
+            assert!(matches!(active_model.uuid, ActiveValue::Set(_)));
+            assert!(matches!(active_model.is_active, ActiveValue::Set(_)));
+        }
+    }
+
+    mod patient_factory_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_patient_with_defaults() {
+            let db = setup_test_db().await;
+            let patient = create_patient(&db).await.unwrap();
+
+            assert_eq!(patient.first_name, "Maria");
+            assert_eq!(patient.last_name, "Silva");
+            assert!(patient.email.contains("@example.com"));
+            assert_eq!(patient.phone, "+5511988888888");
+        }
+
+        #[tokio::test]
+        async fn test_create_patient_with_custom_data() {
+            let db = setup_test_db().await;
+
+            let patient = CreatePatientBuilder::new()
+                .first_name("Carlos".to_string())
+                .last_name("Santos".to_string())
+                .email("carlos@example.com".to_string())
+                .date_of_birth(chrono::NaiveDate::from_ymd_opt(1985, 5, 15).unwrap())
+                .phone("+5511977777777".to_string())
+                .address(Some("Av. Paulista, 1000".to_string()))
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(patient.first_name, "Carlos");
+            assert_eq!(patient.date_of_birth.year(), 1985);
+            assert_eq!(patient.address, Some("Av. Paulista, 1000".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_create_multiple_patients() {
+            let db = setup_test_db().await;
+
+            for i in 0..5 {
+                let _ = CreatePatientBuilder::new()
+                    .first_name(format!("Patient{}", i))
+                    .create(&db)
+                    .await
+                    .unwrap();
+            }
+
+            let count = count_patients(&db).await.unwrap();
+            assert_eq!(count, 5);
+        }
+    }
+
+    mod integration_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_full_hospital_scenario() {
+            let db = setup_test_db().await;
+
+            let cardiology = create_specialty_builder()
+                .name("Cardiology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let neurology = create_specialty_builder()
+                .name("Neurology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let _doctor1 = create_doctor_builder()
+                .specialty_id(cardiology.id)
+                .first_name("Dr. House".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let _doctor2 = create_doctor_builder()
+                .specialty_id(neurology.id)
+                .first_name("Dr. Strange".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let _patient1 = create_patient_builder()
+                .first_name("John".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let _patient2 = create_patient_builder()
+                .first_name("Jane".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+            assert_eq!(count_doctors(&db).await.unwrap(), 2);
+            assert_eq!(count_patients(&db).await.unwrap(), 2);
+        }
+    }
+
+    // ============================================
+    // TESTES - EDGE CASES
+    // ============================================
+
+    mod edge_case_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_special_characters() {
+            let db = setup_test_db().await;
+
+            let specialty = CreateSpecialtyBuilder::new()
+                .name("Test 特殊 🏥".to_string())
+                .description(Some("With émojis".to_string()))
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Test 特殊 🏥");
+        }
+
+        #[tokio::test]
+        async fn test_uuid_uniqueness() {
+            let db = setup_test_db().await;
+            let mut uuids = std::collections::HashSet::new();
+
+            for _ in 0..50 {
+                let specialty = create_specialty(&db).await.unwrap();
+                assert!(uuids.insert(specialty.uuid));
+            }
+
+            assert_eq!(uuids.len(), 50);
+        }
+    }
+
+    mod insert_with_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_uses_custom_insert() {
+            let db = setup_test_db().await;
+            let specialty = create_encrypted_specialty(&db).await.unwrap();
+
+            let found = find_specialty_by_id(&db, specialty.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(found.description, Some("encrypted".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_builder_create_uses_custom_insert() {
+            let db = setup_test_db().await;
+            let specialty = create_encrypted_specialty_builder()
+                .name("Cardiology".to_string())
+                .description(Some("plain text".to_string()))
+                .create(&db)
+                .await
+                .unwrap();
+
+            let found = find_specialty_by_id(&db, specialty.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(found.name, "Cardiology");
+            assert_eq!(found.description, Some("encrypted".to_string()));
+        }
+    }
+
+    mod unique_finder_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_find_specialty_by_uuid() {
+            let db = setup_test_db().await;
+            let created = create_specialty(&db).await.unwrap();
+            let _other = create_specialty(&db).await.unwrap();
+
+            let found = find_specialty_by_uuid(&db, created.uuid).await.unwrap();
+            assert_eq!(found, Some(created));
+        }
+
+        #[tokio::test]
+        async fn test_find_specialty_by_name() {
+            let db = setup_test_db().await;
+            let created = create_specialty_builder()
+                .name("Cardiology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let found = find_specialty_by_name(&db, "Cardiology").await.unwrap();
+            assert_eq!(found.map(|s| s.id), Some(created.id));
+        }
+
+        #[tokio::test]
+        async fn test_get_or_create_specialty_by_name() {
+            let db = setup_test_db().await;
+
+            let (first, created) = get_or_create_specialty_by_name(&db, "Oncology")
+                .await
+                .unwrap();
+            assert!(created);
+            assert_eq!(first.name, "Oncology");
+
+            let (second, created) = get_or_create_specialty_by_name(&db, "Oncology")
+                .await
+                .unwrap();
+            assert!(!created);
+            assert_eq!(second.id, first.id);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_find_specialty_by_uuid_missing() {
+            let db = setup_test_db().await;
+            let found = find_specialty_by_uuid(&db, Uuid::new_v4()).await.unwrap();
+            assert!(found.is_none());
+        }
+    }
+
+    mod complete_mode_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_with_all_columns_listed() {
+            let db = setup_test_db().await;
+            let setting = create_setting(&db).await.unwrap();
+
+            assert!(setting.id > 0);
+            assert!(setting.key.starts_with("setting_"));
+            assert_eq!(setting.value, "enabled");
+        }
+
+        #[tokio::test]
+        async fn test_builder_with_all_columns_listed() {
+            let db = setup_test_db().await;
+            let setting = create_setting_builder()
+                .value("disabled".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(setting.value, "disabled");
+        }
+
+        #[test]
+        fn test_build_sets_every_column() {
+            let active_model = create_setting_builder().id(42).build();
+
+            assert_eq!(active_model.id, ActiveValue::Set(42));
+            assert!(matches!(active_model.key, ActiveValue::Set(_)));
+            assert!(matches!(active_model.value, ActiveValue::Set(_)));
+        }
+
+        // `stamps::ActiveModel` não implementa `Default`: o spread não compilaria aqui
+        #[tokio::test]
+        async fn test_create_without_active_model_default() {
+            let db = setup_test_db().await;
+            let stamp = create_stamp_builder()
+                .code("STP-1")
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert!(stamp.id > 0);
+            assert_eq!(stamp.code, "STP-1");
+            assert!(create_stamp(&db).await.unwrap().code.starts_with("STP-"));
+        }
+    }
+
+    mod backend_default_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_sqlite_branch_is_chosen() {
+            let db = setup_test_db().await;
+            let specialty = create_backend_specialty(&db).await.unwrap();
+
+            assert_eq!(specialty.description, Some("sqlite".to_string()));
+        }
+
+        #[test]
+        fn test_falls_back_to_sqlite_without_connection() {
+            assert_eq!(crate::backend::current_backend(), None);
+            let builder = create_backend_specialty_builder();
+
+            assert_eq!(builder.description, Some("sqlite".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_builder_create_reevaluates_defaults_for_connection_backend() {
+            let db = setup_test_db().await;
+            let builder = crate::backend::with_backend(
+                sea_orm::DatabaseBackend::Postgres,
+                create_backend_specialty_builder,
+            );
+            assert_eq!(builder.description, Some("postgres".to_string()));
+
+            let specialty = builder.create(&db).await.unwrap();
+
+            assert_eq!(specialty.description, Some("sqlite".to_string()));
+        }
+
+        #[tokio::test]
+        async fn test_builder_create_keeps_overridden_fields_across_backends() {
+            let db = setup_test_db().await;
+            let builder = crate::backend::with_backend(sea_orm::DatabaseBackend::Postgres, || {
+                create_backend_specialty_builder().description(Some("kept".to_string()))
+            });
+
+            let specialty = builder.create(&db).await.unwrap();
+
+            assert_eq!(specialty.description, Some("kept".to_string()));
+        }
+
+        #[test]
+        fn test_postgres_branch_inside_backend_scope() {
+            let description = crate::backend::with_backend(
+                sea_orm::DatabaseBackend::Postgres,
+                || crate::backend_default!(sqlite: "sqlite", postgres: "postgres"),
+            );
+
+            assert_eq!(description, "postgres");
+            assert_eq!(crate::backend::current_backend(), None);
+        }
+    }
+
+    mod primary_key_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_get_id_returns_wrapped_type() {
+            let db = setup_test_db().await;
+            let id: SpecialtyId = get_typed_specialty_id(&db).await.unwrap();
+
+            let found = find_specialty_by_id(&db, id.0).await.unwrap();
+            assert!(found.is_some());
+        }
+
+        #[tokio::test]
+        async fn test_create_returning_id_returns_wrapped_type() {
+            let db = setup_test_db().await;
+            let id: SpecialtyId = create_typed_specialty_builder()
+                .name("Typed".to_string())
+                .create_returning_id(&db)
+                .await
+                .unwrap();
+
+            let found = find_specialty_by_id(&db, id.0).await.unwrap().unwrap();
+            assert_eq!(found.name, "Typed");
+        }
+
+        #[tokio::test]
+        async fn test_explicit_primary_key_field() {
+            let db = setup_test_db().await;
+            let id: i32 = get_setting_id(&db).await.unwrap();
+
+            assert!(id > 0);
+        }
+    }
+
+    mod update_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_update_only_changes_overridden_fields() {
+            let db = setup_test_db().await;
+            let created = create_specialty_builder()
+                .description(Some("Original".to_string()))
+                .is_active(false)
+                .create(&db)
+                .await
+                .unwrap();
+
+            let updated = update_specialty(&db, created.id, |b| b.name("Renamed".to_string()))
+                .await
+                .unwrap();
+
+            assert_eq!(updated.id, created.id);
+            assert_eq!(updated.name, "Renamed");
+            assert_eq!(updated.uuid, created.uuid);
+            assert_eq!(updated.description, Some("Original".to_string()));
+            assert!(!updated.is_active);
+
+            let found = find_specialty_by_id(&db, created.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(found, updated);
+        }
+
+        #[tokio::test]
+        async fn test_update_lazy_field() {
+            let db = setup_test_db().await;
+            let doctor = create_doctor(&db).await.unwrap();
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let updated = update_doctor(&db, doctor.id, |b| b.specialty_id(specialty.id))
+                .await
+                .unwrap();
+
+            assert_eq!(updated.specialty_id, specialty.id);
+            assert_eq!(updated.email, doctor.email);
+        }
+
+        #[tokio::test]
+        async fn test_update_missing_row() {
+            let db = setup_test_db().await;
+            let result = update_specialty(&db, 999, |b| b.name("Nope".to_string())).await;
+
+            assert!(matches!(result, Err(DbErr::RecordNotFound(_))));
+        }
+
+        #[tokio::test]
+        async fn test_update_does_not_evaluate_defaults() {
+            let db = setup_test_db().await;
+            let created = create_sequenced_specialty(&db).await.unwrap();
+            reset_sequenced_specialty_sequence();
+
+            let updated = update_sequenced_specialty(&db, created.id, |b| b.is_active(false))
+                .await
+                .unwrap();
+
+            assert_eq!(updated.name, created.name);
+            assert_eq!(sequenced_specialty_sequence_next(), 1);
+        }
+    }
+
+    mod stream_tests {
+        use super::*;
+        use futures::StreamExt;
+
+        #[tokio::test]
+        async fn test_consume_stream() {
+            let db = setup_test_db().await;
+
+            let specialties: Vec<_> = create_specialty_stream(&db, 100).collect().await;
+
+            assert_eq!(specialties.len(), 100);
+            assert!(specialties.iter().all(|result| result.is_ok()));
+            assert_eq!(count_specialties(&db).await.unwrap(), 100);
+        }
+
+        #[tokio::test]
+        async fn test_stream_only_inserts_when_polled() {
+            let db = setup_test_db().await;
+
+            let stream = create_specialty_stream(&db, 100);
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+
+            let taken: Vec<_> = stream.take(3).collect().await;
+            assert_eq!(taken.len(), 3);
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+    }
+
+    mod partial_lazy_field_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_lazy_field_reads_partial_model() {
+            let db = setup_test_db().await;
+            let doctor = create_licensed_doctor(&db).await.unwrap();
+
+            assert_eq!(doctor.license_number, "LIC-DOE");
+            assert!(doctor.specialty_id > 0);
+        }
+
+        #[tokio::test]
+        async fn test_lazy_field_reads_builder_override() {
+            let db = setup_test_db().await;
+            let doctor = create_licensed_doctor_builder()
+                .last_name("House".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.license_number, "LIC-HOUSE");
+        }
+
+        #[tokio::test]
+        async fn test_partial_lazy_field_can_be_overridden() {
+            let db = setup_test_db().await;
+            let doctor = create_licensed_doctor_builder()
+                .license_number("CUSTOM".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.license_number, "CUSTOM");
+        }
+
+        #[tokio::test]
+        async fn test_lazy_field_reads_earlier_lazy_field() {
+            let db = setup_test_db().await;
+            let doctor = create_chained_lazy_doctor(&db).await.unwrap();
+
+            assert_eq!(
+                doctor.license_number,
+                format!("LIC-{}", doctor.specialty_id)
+            );
+
+            let specialty = create_specialty(&db).await.unwrap();
+            let doctor = create_chained_lazy_doctor_builder()
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.license_number, format!("LIC-{}", specialty.id));
+        }
+    }
+
+    mod params_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_from_params() {
+            let db = setup_test_db().await;
+            let mut params = SpecialtyParams::from(create_specialty_builder());
+            params.name = "From Params".to_string();
+            params.is_active = false;
+
+            let specialty = create_specialty_from_params(&db, params.clone())
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "From Params");
+            assert_eq!(specialty.uuid, params.uuid);
+            assert!(!specialty.is_active);
+        }
+
+        // O padrão exigido de outros crates pelo `#[non_exhaustive]`
+        #[test]
+        #[allow(clippy::field_reassign_with_default)]
+        fn test_params_built_from_default_and_assignments() {
+            let mut params = SpecialtyParams::default();
+            params.name = "Assigned".to_string();
+
+            let built = CreateSpecialtyBuilder::from(params).build();
+
+            assert_eq!(built.name, ActiveValue::Set("Assigned".to_string()));
+            assert_eq!(built.is_active, ActiveValue::Set(true));
+        }
+
+        #[test]
+        fn test_into_params_round_trip() {
+            let mut params = DoctorParams::from(create_doctor_builder());
+            params.first_name = "Gregory".to_string();
+            params.specialty_id = Some(7);
+
+            let round_trip = CreateDoctorBuilder::from(params.clone()).into_params();
+
+            assert_eq!(format!("{round_trip:?}"), format!("{params:?}"));
+            assert_eq!(create_doctor_builder().into_params().specialty_id, None);
+        }
+    }
+
+    #[cfg(feature = "proptest")]
+    mod arbitrary_tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(16))]
+
+            #[test]
+            fn test_arbitrary_params_insert(params in any::<SpecialtyParams>()) {
+                let runtime = tokio::runtime::Runtime::new().unwrap();
+                let specialty = runtime
+                    .block_on(async {
+                        let db = setup_test_db().await;
+                        create_specialty_from_params(&db, params.clone()).await
+                    })
+                    .unwrap();
+
+                prop_assert_eq!(specialty.name, params.name);
+                prop_assert_eq!(specialty.is_active, params.is_active);
+                prop_assert_eq!(specialty.description, Some("Test Description".to_string()));
+            }
+        }
+    }
+
+    mod diff_from_defaults_tests {
+        use super::*;
+
+        #[test]
+        fn test_diff_contains_overridden_field() {
+            let builder = create_specialty_builder().name("Cardiology".to_string());
+
+            assert_eq!(builder.diff_from_defaults(), vec!["name"]);
+        }
+
+        #[test]
+        fn test_diff_ignores_value_equal_to_default() {
+            let builder = create_specialty_builder().is_active(true);
+
+            assert!(builder.diff_from_defaults().is_empty());
+        }
+
+        #[test]
+        fn test_diff_contains_set_lazy_field() {
+            let builder = create_doctor_builder()
+                .first_name("Gregory".to_string())
+                .specialty_id(1);
+
+            assert_eq!(
+                builder.diff_from_defaults(),
+                vec!["first_name", "specialty_id"]
+            );
+        }
+
+        #[test]
+        fn test_non_partial_eq_values_are_not_compared() {
+            use crate::diff::{Compare, CompareFallback as _, ComparePartialEq as _};
+
+            struct Opaque;
+
+            assert_eq!((&Compare(&Opaque, &Opaque)).differs(), None);
+            assert_eq!(Compare(&1, &2).differs(), Some(true));
+        }
+    }
+
+    mod retry_on_conflict_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_retries_after_unique_violation() {
+            let db = setup_test_db().await;
+            let next = RETRY_KEY.load(std::sync::atomic::Ordering::SeqCst);
+            let conflicting = format!("retry-{}", next);
+            create_setting_builder()
+                .key(conflicting.clone())
+                .create(&db)
+                .await
+                .unwrap();
+
+            let setting = create_retrying_setting(&db).await.unwrap();
+
+            assert_ne!(setting.key, conflicting);
+            assert_eq!(setting.key, format!("retry-{}", next + 1));
+        }
+
+        #[tokio::test]
+        async fn test_only_unique_violations_are_retryable() {
+            let db = setup_test_db().await;
+            let existing = create_setting(&db).await.unwrap();
+
+            let conflict = create_setting_builder()
+                .key(existing.key)
+                .create(&db)
+                .await
+                .unwrap_err();
+            assert!(crate::retry::is_unique_violation(&conflict));
+
+            let empty_db = Database::connect("sqlite::memory:").await.unwrap();
+            let missing_table = create_setting(&empty_db).await.unwrap_err();
+            assert!(!crate::retry::is_unique_violation(&missing_table));
+        }
+    }
+
+    mod seed_fixtures_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_seed_named_specialties() {
+            let db = setup_test_db().await;
+
+            let specialties = seed_specialty_fixtures(
+                &db,
+                &[
+                    |b| b.name("Cardiology".to_string()),
+                    |b| b.name("Neurology".to_string()),
+                    |b| b.name("Surgery".to_string()).is_active(false),
+                ],
+            )
+            .await
+            .unwrap();
+
+            let names: Vec<_> = specialties.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(names, ["Cardiology", "Neurology", "Surgery"]);
+            assert!(!specialties[2].is_active);
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+    }
+
+    #[expect(
+        dead_code,
+        reason = "public mode leaves unused generated items to dead-code analysis"
+    )]
+    mod public_mode {
+        use super::*;
+
+        define_factory! {
+            /// Cria uma specialty para uso fora dos testes
+            public_specialty => specialties::Model {
+                active_model: specialties::ActiveModel,
+                fields: {
+                    name: String = "Public Specialty".to_string(),
+                    description: Option<String> = None,
+                    uuid: Uuid = Uuid::new_v4(),
+                    is_active: bool = true,
+                },
+                unique: [uuid],
+                public: true
+            }
+        }
+    }
+
+    mod public_mode_tests {
+        use super::public_mode::*;
+        use super::*;
+
+        #[tokio::test]
+        async fn test_public_factory_creates() {
+            let db = setup_test_db().await;
+
+            let specialty = create_public_specialty_builder()
+                .name("Seeded".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+            let found = find_public_specialty_by_uuid(&db, specialty.uuid)
+                .await
+                .unwrap();
+
+            assert_eq!(found, Some(specialty));
+        }
+    }
+
+    mod generated_columns_tests {
+        use super::*;
+
+        #[test]
+        fn test_generated_columns_are_not_set() {
+            let active_model = create_ticket_builder().build();
+
+            assert_eq!(active_model.id, ActiveValue::NotSet);
+            assert_eq!(active_model.status, ActiveValue::NotSet);
+            assert!(matches!(active_model.title, ActiveValue::Set(_)));
+        }
+
+        #[tokio::test]
+        async fn test_generated_columns_use_database_default() {
+            let db = setup_test_db().await;
+            let ticket = create_ticket(&db).await.unwrap();
+
+            assert!(ticket.id > 0);
+            assert_eq!(ticket.status, "open");
+        }
+    }
+
+    mod derive_factory_tests {
+        use super::labels::{CreateLabelBuilder, create_label, create_label_builder};
+        use super::*;
+
+        #[tokio::test]
+        async fn test_derived_factory_with_defaults() {
+            let db = setup_test_db().await;
+            let label = create_label(&db).await.unwrap();
+
+            assert!(label.id > 0);
+            assert_eq!(label.name, "Urgent");
+            assert_eq!(label.color, "#ff0000");
+            assert!(label.specialty_id > 0);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_derived_factory_builder() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let label = create_label_builder()
+                .name("Low".to_string())
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(label.name, "Low");
+            assert_eq!(label.specialty_id, specialty.id);
+        }
+
+        #[test]
+        fn test_derived_factory_build() {
+            let active_model = CreateLabelBuilder::new().specialty_id(1).build();
+
+            assert_eq!(active_model.id, ActiveValue::NotSet);
+            assert_eq!(active_model.color, ActiveValue::Set("#ff0000".to_string()));
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty com descrição numerada
+        described_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Described".to_string(),
+                description: Option<String> = Some(format!("Desc {}", described_specialty_sequence_next())),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    mod sibling_default_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_default_reads_sibling_defaults() {
+            let db = setup_test_db().await;
+
+            let doctor = create_named_doctor(&db).await.unwrap();
+
+            assert_eq!(doctor.email, "jane.roe@example.com");
+            assert_eq!(doctor.license_number, "LIC-ROE");
+        }
+
+        #[tokio::test]
+        async fn test_builder_defaults_read_sibling_defaults() {
+            let db = setup_test_db().await;
+
+            let doctor = create_named_doctor_builder()
+                .first_name("Ann".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.first_name, "Ann");
+            assert_eq!(doctor.email, "ann.roe@example.com");
+            assert_eq!(doctor.license_number, "LIC-ROE");
+        }
+
+        #[tokio::test]
+        async fn test_setter_keeps_generated_defaults() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+            let stored = create_named_doctor_builder()
+                .first_name("Ann".to_string())
+                .specialty_id(specialty.id);
+
+            let first = stored.clone().build();
+            let second = stored.clone().build();
+            let preview = stored.preview();
+            let doctor = stored.create(&db).await.unwrap();
+
+            assert_eq!(first.uuid, second.uuid);
+            assert_eq!(first.uuid, ActiveValue::Set(doctor.uuid));
+            assert_eq!(preview.uuid, doctor.uuid);
+            assert_eq!(preview.email, "ann.roe@example.com");
+        }
+
+        #[tokio::test]
+        async fn test_setter_keeps_sequence_numbering() {
+            let db = setup_test_db().await;
+            let _scope = described_specialty_sequence_scope();
+
+            let first = create_described_specialty_builder()
+                .name("First")
+                .create(&db)
+                .await
+                .unwrap();
+            let second = create_described_specialty_builder()
+                .name("Second")
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(first.description.as_deref(), Some("Desc 1"));
+            assert_eq!(second.description.as_deref(), Some("Desc 2"));
+        }
+    }
+
+    mod params_default_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_from_struct_update_params() {
+            let db = setup_test_db().await;
+
+            let specialty = create_specialty_from(
+                &db,
+                SpecialtyParams {
+                    name: "Oncology".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(specialty.name, "Oncology");
+            assert!(specialty.is_active);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_params_default_leaves_lazy_fields_unset() {
+            let db = setup_test_db().await;
+
+            let params = DoctorParams::default();
+            assert_eq!(params.first_name, "John");
+            assert!(params.specialty_id.is_none());
+
+            let doctor = create_doctor_from(&db, params).await.unwrap();
+            let specialty = find_specialty_by_id(&db, doctor.specialty_id)
+                .await
+                .unwrap();
+            assert!(specialty.is_some());
+        }
+    }
+
+    mod datetime_tests {
+        use super::*;
+        use crate::datetime::now_in;
+
+        #[tokio::test]
+        async fn test_now_in_default_keeps_timezone_offset() {
+            let db = setup_test_db().await;
+
+            let appointment = create_appointment(&db).await.unwrap();
+
+            // O SQLite devolve o valor em UTC, então o offset é conferido no texto salvo
+            let row = db
+                .query_one(sea_orm::Statement::from_string(
+                    db.get_database_backend(),
+                    "SELECT scheduled_at FROM appointments",
+                ))
+                .await
+                .unwrap()
+                .unwrap();
+            let stored: String = row.try_get("", "scheduled_at").unwrap();
+            let stored = chrono::DateTime::parse_from_rfc3339(&stored).unwrap();
+
+            // America/Sao_Paulo não tem horário de verão desde 2019
+            assert_eq!(stored.offset().local_minus_utc(), -3 * 3600);
+            assert_eq!(stored, appointment.scheduled_at);
+        }
+
+        #[test]
+        fn test_now_in_default_is_set_on_build() {
+            let model = create_appointment_builder().build();
+            let scheduled_at = model.scheduled_at.unwrap();
+
+            assert_eq!(scheduled_at.offset().local_minus_utc(), -3 * 3600);
+        }
+
+        #[test]
+        fn test_now_in_matches_utc_instant() {
+            let before = chrono::Utc::now();
+            let now = now_in("Asia/Tokyo");
+
+            assert_eq!(now.offset().local_minus_utc(), 9 * 3600);
+            assert!(now >= before);
+        }
+
+        #[test]
+        #[should_panic(expected = "unknown timezone 'Mars/Olympus'")]
+        fn test_now_in_rejects_unknown_timezone() {
+            now_in("Mars/Olympus");
+        }
+    }
+
+    mod create_all_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_all_inserts_in_order() {
+            let db = setup_test_db().await;
+
+            let specialties = create_specialties(
+                &db,
+                vec![
+                    create_specialty_builder().name("Cardiology".to_string()),
+                    create_specialty_builder().name("Neurology".to_string()),
+                ],
+            )
+            .await
+            .unwrap();
+
+            let names: Vec<_> = specialties.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(names, ["Cardiology", "Neurology"]);
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_create_all_rolls_back_on_failure() {
+            let db = setup_test_db().await;
+
+            let result = create_settings(
+                &db,
+                vec![
+                    create_setting_builder().key("duplicated".to_string()),
+                    create_setting_builder().key("duplicated".to_string()),
+                ],
+            )
+            .await;
+
+            assert!(result.is_err());
+            assert_eq!(settings::Entity::find().count(&db).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_create_all_resolves_lazy_fields() {
+            let db = setup_test_db().await;
+
+            let doctors =
+                create_doctors(&db, vec![create_doctor_builder(), create_doctor_builder()])
+                    .await
+                    .unwrap();
+
+            assert_eq!(doctors.len(), 2);
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_create_all_rolls_back_lazy_parents() {
+            let db = setup_test_db().await;
+
+            let result = create_doctors(
+                &db,
+                vec![
+                    create_doctor_builder(),
+                    create_doctor_builder().set_specialty_id_with(|_db| async {
+                        Err(DbErr::Custom("no specialty".to_string()))
+                    }),
+                ],
+            )
+            .await;
+
+            assert!(result.is_err());
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+            assert_eq!(doctors::Entity::find().count(&db).await.unwrap(), 0);
+        }
+    }
+
+    #[cfg(feature = "factory-log")]
+    mod factory_log_tests {
+        use super::*;
+        use crate::{clear_factory_log, factory_log};
+
+        #[tokio::test]
+        async fn test_factory_log_records_creations_in_order() {
+            let db = setup_test_db().await;
+            clear_factory_log();
+
+            let specialty = create_specialty(&db).await.unwrap();
+            let doctor = create_doctor_builder()
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
+
+            let log = factory_log();
+            assert_eq!(log.len(), 2);
+            assert_eq!(log[0].table, "specialties");
+            assert_eq!(log[0].id, specialty.id.into());
+            assert_eq!(log[1].table, "doctors");
+            assert_eq!(log[1].id, doctor.id.into());
+
+            clear_factory_log();
+            assert!(factory_log().is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_factory_log_includes_lazy_parents() {
+            let db = setup_test_db().await;
+            clear_factory_log();
+
+            create_doctor(&db).await.unwrap();
+
+            let tables: Vec<_> = factory_log().into_iter().map(|entry| entry.table).collect();
+            assert_eq!(tables, ["specialties", "doctors"]);
+        }
+    }
+
+    mod soft_delete_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_leaves_record_alive() {
+            let db = setup_test_db().await;
+
+            let post = create_post(&db).await.unwrap();
+            assert_eq!(post.deleted_at, None);
+
+            let post = create_post_builder()
+                .title("Draft".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+            assert_eq!(post.deleted_at, None);
+        }
+
+        #[tokio::test]
+        async fn test_create_deleted_sets_deleted_at() {
+            let db = setup_test_db().await;
+
+            let before = chrono::Utc::now();
+            let post = create_post_deleted(&db).await.unwrap();
+
+            assert_eq!(post.title, "Hello");
+            assert!(
+                post.deleted_at
+                    .is_some_and(|deleted_at| deleted_at >= before)
+            );
+        }
+    }
+
+    mod list_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_list() {
+            let db = setup_test_db().await;
+
+            let specialties = create_specialty_list(&db, 3).await.unwrap();
+
+            assert_eq!(specialties.len(), 3);
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_create_bulk_ids() {
+            let db = setup_test_db().await;
+            create_specialty(&db).await.unwrap();
+
+            let ids = create_specialty_bulk_ids(&db, 3).await.unwrap();
+
+            assert_eq!(ids.len(), 3);
+            assert_eq!(ids, [ids[0], ids[0] + 1, ids[0] + 2]);
+            for id in ids {
+                assert!(find_specialty_by_id(&db, id).await.unwrap().is_some());
+            }
+        }
+
+        #[tokio::test]
+        async fn test_create_list_unique_suffix() {
+            let db = setup_test_db().await;
+
+            let specialties = create_specialty_builder()
+                .name("Ward")
+                .create_list_unique_suffix(&db, 5)
+                .await
+                .unwrap();
+
+            let names: Vec<_> = specialties.iter().map(|s| s.name.as_str()).collect();
+            assert_eq!(names, ["Ward-0", "Ward-1", "Ward-2", "Ward-3", "Ward-4"]);
+            assert_eq!(count_specialties(&db).await.unwrap(), 5);
+        }
+
+        #[tokio::test]
+        async fn test_create_through_connection_methods() {
+            let db = setup_test_db().await;
+
+            let specialty = db.create_specialty().await.unwrap();
+            let specialties = db.create_specialty_list(2).await.unwrap();
+
+            assert_eq!(specialty.name, "Test Specialty");
+            assert_eq!(specialties.len(), 2);
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_create_list_ids() {
+            let db = setup_test_db().await;
+
+            let ids = create_specialty_list_ids(&db, 3).await.unwrap();
+
+            assert_eq!(ids.len(), 3);
+            assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_create_list_with_index() {
+            let db = setup_test_db().await;
+
+            let specialties =
+                create_specialty_list_with(&db, 2, |i, b| b.name(format!("item-{i}")))
+                    .await
+                    .unwrap();
+
+            let names: Vec<_> = specialties.into_iter().map(|s| s.name).collect();
+            assert_eq!(names, ["item-0", "item-1"]);
+        }
+
+        #[tokio::test]
+        async fn test_create_map_by_key() {
+            let db = setup_test_db().await;
+
+            let specialties = create_specialty_map_by(
+                &db,
+                &["Cardiology", "Neurology", "Oncology"],
+                |name, b| b.name(*name),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(specialties.len(), 3);
+            for (name, specialty) in &specialties {
+                assert_eq!(specialty.name, *name);
+                let found = find_specialty_by_name(&db, *name).await.unwrap().unwrap();
+                assert_eq!(found.id, specialty.id);
+            }
+        }
+
+        #[tokio::test]
+        async fn test_create_list_from_continues_numbering() {
+            let db = setup_test_db().await;
+            let name = |i: usize, b: CreateSpecialtyBuilder| b.name(format!("item-{i}"));
+
+            let first = create_specialty_list_from(&db, 0, 3, name).await.unwrap();
+            let second = create_specialty_list_from(&db, first.len(), 3, name)
+                .await
+                .unwrap();
+
+            let names: Vec<_> = first.into_iter().chain(second).map(|s| s.name).collect();
+            assert_eq!(
+                names,
+                ["item-0", "item-1", "item-2", "item-3", "item-4", "item-5"]
+            );
+        }
+    }
+
+    mod dyn_factory_tests {
+        use super::*;
+        use crate::DynFactory;
+
+        #[tokio::test]
+        async fn test_dyn_factories_in_a_vec() {
+            let db = setup_test_db().await;
+            let seeders: Vec<Box<dyn DynFactory>> =
+                vec![Box::new(SpecialtyFactory), Box::new(PatientFactory)];
+
+            for seeder in &seeders {
+                seeder.create_default(&db).await.unwrap();
+            }
+
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+            assert_eq!(count_patients(&db).await.unwrap(), 1);
+        }
+    }
+
+    mod unset_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_unset_field_uses_db_default() {
+            let db = setup_test_db().await;
+
+            let member = create_member(&db).await.unwrap();
+            assert!(member.is_active);
+
+            let member = create_member_builder()
+                .unset_is_active()
+                .create(&db)
+                .await
+                .unwrap();
+            assert!(!member.is_active);
+        }
+
+        #[tokio::test]
+        async fn test_setter_after_unset_sets_the_field_again() {
+            let db = setup_test_db().await;
+
+            let member = create_member_builder()
+                .unset_is_active()
+                .is_active(true)
+                .create(&db)
+                .await
+                .unwrap();
+            assert!(member.is_active);
+        }
+
+        #[test]
+        fn test_unset_lazy_field_on_build() {
+            let builder = create_doctor_builder().unset_specialty_id();
+            assert!(!builder.requires_db());
+
+            let model = builder.build();
+            assert_eq!(model.specialty_id, ActiveValue::NotSet);
+            assert!(model.first_name.is_set());
+        }
+    }
+
+    mod factory_error_tests {
+        use super::*;
+        use crate::FactoryError;
+        use std::error::Error as _;
+
+        #[test]
+        fn test_try_build_reports_missing_lazy_field() {
+            let err = create_doctor_builder().try_build().unwrap_err();
+
+            assert!(matches!(
+                err,
+                FactoryError::MissingLazyField("specialty_id")
+            ));
+            assert_eq!(err.to_string(), "lazy field 'specialty_id' is not set");
+        }
+
+        #[test]
+        fn test_try_build_with_every_lazy_field_set() {
+            let model = create_doctor_builder().specialty_id(7).try_build().unwrap();
+
+            assert_eq!(model.specialty_id, ActiveValue::Set(7));
+        }
+
+        #[test]
+        fn test_db_variant_from_db_err() {
+            fn lookup() -> Result<(), FactoryError> {
+                Err(DbErr::RecordNotFound("specialty".to_string()))?
+            }
+
+            let err = lookup().unwrap_err();
+            assert!(matches!(err, FactoryError::Db(DbErr::RecordNotFound(_))));
+            assert!(err.source().is_some());
+        }
+
+        #[test]
+        fn test_validation_and_bad_override_variants() {
+            let err = FactoryError::Validation("name is empty".to_string());
+            assert!(
+                matches!(&err, FactoryError::Validation(message) if message == "name is empty")
+            );
+            assert_eq!(err.to_string(), "validation failed: name is empty");
+            assert!(err.source().is_none());
+
+            let err = FactoryError::BadOverride {
+                field: "is_active".to_string(),
+                message: "expected a boolean".to_string(),
+            };
+            assert!(
+                matches!(&err, FactoryError::BadOverride { field, .. } if field == "is_active")
+            );
+            assert_eq!(
+                err.to_string(),
+                "bad override for field 'is_active': expected a boolean"
+            );
+        }
+    }
+
+    mod create_if_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_if_false_skips_insert() {
+            let db = setup_test_db().await;
+
+            let doctor = create_doctor_builder().create_if(&db, false).await.unwrap();
+
+            assert!(doctor.is_none());
+            assert_eq!(count_doctors(&db).await.unwrap(), 0);
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_create_if_true_inserts() {
+            let db = setup_test_db().await;
+
+            let specialty = create_specialty_builder()
+                .name("Conditional".to_string())
+                .create_if(&db, true)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.map(|s| s.name).as_deref(), Some("Conditional"));
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+    }
+
+    mod transform_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_transform_applies_to_defaults() {
+            let db = setup_test_db().await;
+
+            let doctor = create_normalized_doctor(&db).await.unwrap();
+
+            assert!(doctor.email.starts_with("doctor_"));
+            assert_eq!(doctor.email, doctor.email.to_lowercase());
+            assert_eq!(doctor.license_number, "LIC_NORMALIZED");
+        }
+
+        #[tokio::test]
+        async fn test_transform_applies_to_builder_overrides() {
+            let db = setup_test_db().await;
+
+            let doctor = create_normalized_doctor_builder()
+                .email("Mixed.Case@Example.COM".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.email, "mixed.case@example.com");
+        }
+
+        #[tokio::test]
+        async fn test_transform_applies_to_lists_and_updates() {
+            let db = setup_test_db().await;
+
+            let doctors = create_normalized_doctor_list_with(&db, 2, |i, b| {
+                b.email(format!("Doctor.{i}@Example.com"))
+            })
+            .await
+            .unwrap();
+            let emails: Vec<_> = doctors.iter().map(|d| d.email.as_str()).collect();
+            assert_eq!(emails, ["doctor.0@example.com", "doctor.1@example.com"]);
+
+            let updated = update_normalized_doctor(&db, doctors[0].id, |b| {
+                b.email("UPDATED@Example.com".to_string())
+            })
+            .await
+            .unwrap();
+            assert_eq!(updated.email, "updated@example.com");
+            assert_eq!(updated.license_number, "LIC_NORMALIZED");
+        }
+
+        #[test]
+        fn test_transform_applies_to_build() {
+            let model = create_normalized_doctor_builder()
+                .email("BUILD@Example.com".to_string())
+                .specialty_id(1)
+                .build();
+
+            assert_eq!(
+                model.email,
+                ActiveValue::Set("build@example.com".to_string())
+            );
+        }
+    }
+
+    mod into_active_model_tests {
+        use super::*;
+
+        fn name_of(active: specialties::ActiveModel) -> ActiveValue<String> {
+            active.name
+        }
+
+        #[test]
+        fn test_builder_into_active_model() {
+            let uuid = Uuid::new_v4();
+            let active: doctors::ActiveModel = create_doctor_builder()
+                .first_name("Ana".to_string())
+                .uuid(uuid)
+                .specialty_id(3)
+                .into();
+
+            assert_eq!(active.first_name, ActiveValue::Set("Ana".to_string()));
+            assert_eq!(active.uuid, ActiveValue::Set(uuid));
+            assert_eq!(active.specialty_id, ActiveValue::Set(3));
+            assert_eq!(active.id, ActiveValue::NotSet);
+
+            let name = name_of(create_specialty_builder().name("Into".to_string()).into());
+            assert_eq!(name, ActiveValue::Set("Into".to_string()));
+        }
+
+        #[test]
+        #[should_panic(expected = "lazy field 'specialty_id' must be set when using build()")]
+        fn test_builder_into_active_model_panics_without_lazy_field() {
+            let _: doctors::ActiveModel = create_doctor_builder().into();
+        }
+    }
+
+    mod returns_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_mapped_returns_projection() {
+            let db = setup_test_db().await;
+
+            let summary = create_summary_specialty_mapped(&db).await.unwrap();
+            let stored = find_specialty_by_id(&db, summary.id)
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(summary.label, "SUMMARY");
+            assert_eq!(stored.name, "Summary");
+
+            let summary = create_summary_specialty_builder()
+                .name("Cardio".to_string())
+                .create_mapped(&db)
+                .await
+                .unwrap();
+            assert_eq!(
+                summary,
+                SpecialtySummary {
+                    id: summary.id,
+                    label: "CARDIO".to_string()
+                }
+            );
+        }
+
+        #[tokio::test]
+        async fn test_create_mapped_defaults_to_identity() {
+            let db = setup_test_db().await;
+
+            let specialty: specialties::Model = create_specialty_mapped(&db).await.unwrap();
+
+            assert_eq!(specialty.name, "Test Specialty");
+        }
+    }
+
+    mod lazy_defaults_tests {
+        use super::*;
+
+        fn uuid_of(active: specialties::ActiveModel) -> Uuid {
+            active.uuid.unwrap()
+        }
+
+        #[test]
+        fn test_lazy_defaults_regenerates_on_each_build() {
+            let stored = create_fresh_specialty_builder();
+
+            assert_ne!(uuid_of(stored.clone().build()), uuid_of(stored.build()));
+        }
+
+        #[test]
+        fn test_lazy_defaults_keeps_overridden_fields() {
+            let uuid = Uuid::new_v4();
+            let stored = create_fresh_specialty_builder().uuid(uuid);
+
+            assert_eq!(uuid_of(stored.clone().build()), uuid);
+            assert_eq!(uuid_of(stored.build()), uuid);
+        }
+
+        #[test]
+        fn test_defaults_are_captured_at_new_without_flag() {
+            let stored = create_specialty_builder();
+
+            assert_eq!(uuid_of(stored.clone().build()), uuid_of(stored.build()));
+        }
+
+        #[tokio::test]
+        async fn test_lazy_defaults_regenerates_on_create() {
+            let db = setup_test_db().await;
+            let stored = create_fresh_specialty_builder();
+
+            let first = stored.clone().create(&db).await.unwrap();
+            let second = stored.create(&db).await.unwrap();
+
+            assert_ne!(first.uuid, second.uuid);
+        }
+    }
+
+    mod schema_tests {
+        use super::*;
+
+        #[test]
+        fn test_in_schema_qualifies_the_table() {
+            let sql = create_specialty_builder()
+                .in_schema("tenant_42")
+                .insert_statement(sea_orm::DatabaseBackend::Postgres)
+                .to_string();
+
+            assert!(
+                sql.starts_with(r#"INSERT INTO "tenant_42"."specialties""#),
+                "{sql}"
+            );
+        }
+
+        #[test]
+        fn test_insert_statement_without_schema() {
+            let sql = create_specialty_builder()
+                .name("Plain".to_string())
+                .insert_statement(sea_orm::DatabaseBackend::Postgres)
+                .to_string();
+
+            assert!(sql.starts_with(r#"INSERT INTO "specialties""#), "{sql}");
+            assert!(sql.contains("'Plain'"), "{sql}");
+        }
+    }
+
+    mod cleanup_guard_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_guard_cleanup_deletes_row() {
+            let db = setup_test_db().await;
+
+            let (specialty, guard) = create_specialty_guarded(&db).await.unwrap();
+            let _other = create_specialty(&db).await.unwrap();
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+
+            guard.cleanup().await.unwrap();
+
+            assert!(
+                find_specialty_by_id(&db, specialty.id)
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_dropped_guard_deletes_row_in_background() {
+            let db = setup_test_db().await;
+
+            let (specialty, guard) = create_specialty_guarded(&db).await.unwrap();
+            drop(guard);
+            for _ in 0..100 {
+                if find_specialty_by_id(&db, specialty.id)
+                    .await
+                    .unwrap()
+                    .is_none()
+                {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+
+            assert!(
+                find_specialty_by_id(&db, specialty.id)
+                    .await
+                    .unwrap()
+                    .is_none()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_guarded_cascade_deletes_children_first() {
+            let db = setup_test_db().await;
+            let (specialty, guard) = create_specialty_guarded_cascade(&db).await.unwrap();
+            for _ in 0..2 {
+                create_doctor_builder()
+                    .specialty_id(specialty.id)
+                    .create(&db)
+                    .await
+                    .unwrap();
+            }
+
+            guard.cleanup().await.unwrap();
+
+            assert_eq!(count_doctors(&db).await.unwrap(), 0);
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+        }
+    }
+
+    mod column_of_tests {
+        use super::*;
+
+        #[test]
+        fn test_column_of_maps_fields_to_columns() {
+            assert!(matches!(
+                specialty_column_of("name"),
+                Some(specialties::Column::Name)
+            ));
+            assert!(matches!(
+                specialty_column_of("is_active"),
+                Some(specialties::Column::IsActive)
+            ));
+            assert!(matches!(
+                doctor_column_of("specialty_id"),
+                Some(doctors::Column::SpecialtyId)
+            ));
+            assert!(matches!(
+                ticket_column_of("status"),
+                Some(tickets::Column::Status)
+            ));
+        }
+
+        #[test]
+        fn test_column_of_unknown_field() {
+            assert!(specialty_column_of("missing").is_none());
+            assert!(specialty_column_of("id").is_none());
+        }
+    }
+
+    mod extends_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_extended_factory_inherits_base_defaults() {
+            let db = setup_test_db().await;
+
+            let admin = create_admin_doctor(&db).await.unwrap();
+
+            assert_eq!(admin.first_name, "John");
+            assert_eq!(admin.last_name, "Doe");
+            assert!(admin.is_active);
+            assert!(admin.email.starts_with("admin_"));
+            assert!(admin.email.ends_with("@hospital.com"));
+            assert_eq!(admin.phone, None);
+            assert!(
+                find_specialty_by_id(&db, admin.specialty_id)
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+        }
+
+        #[tokio::test]
+        async fn test_extended_builder_keeps_base_methods() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let admin = create_admin_doctor_builder()
+                .first_name("Root".to_string())
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
+            assert_eq!(admin.first_name, "Root");
+            assert!(admin.email.starts_with("admin_"));
+
+            let admins = create_admin_doctor_list(&db, 2).await.unwrap();
+            assert_ne!(admins[0].email, admins[1].email);
+            assert_eq!(count_doctors(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_extended_factory_generates_base_helpers() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let builder: CreateAdminDoctorBuilder = create_admin_doctor_builder();
+            assert_eq!(builder.diff_from_defaults(), Vec::<&str>::new());
+            let admin = create_admin_doctor_for(&db, &specialty).await.unwrap();
+            assert_eq!(admin.specialty_id, specialty.id);
+            assert!(admin.email.starts_with("admin_"));
+
+            let params = AdminDoctorParams::default();
+            assert!(params.is_active);
+            assert_eq!(params.phone, None);
+
+            let admins = create_admin_doctors(&db, vec![create_admin_doctor_builder()])
+                .await
+                .unwrap();
+            assert!(admins[0].email.starts_with("admin_"));
+            assert!(matches!(
+                create_admin_doctor_builder()
+                    .email(admin.email.clone())
+                    .try_create(&db)
+                    .await,
+                Err(crate::FactoryError::Duplicate { field: "email" })
+            ));
+        }
+
+        #[tokio::test]
+        async fn test_extended_factory_adds_fields() {
+            let db = setup_test_db().await;
+
+            let plain = create_plain_specialty(&db).await.unwrap();
+            let annotated = create_annotated_specialty(&db).await.unwrap();
+            let renamed = create_annotated_specialty_builder()
+                .name("Oncology".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(plain.description, None);
+            assert_eq!(annotated.name, "Annotated Specialty");
+            assert_eq!(
+                annotated.description.as_deref(),
+                Some("About Annotated Specialty")
+            );
+            assert_eq!(renamed.description.as_deref(), Some("About Oncology"));
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_public_extended_factory_creates() {
+            let db = setup_test_db().await;
+
+            let specialty = public_extension::create_public_plain_specialty(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Plain Specialty");
+        }
+    }
+
+    mod concurrent_list_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_list_concurrent() {
+            let db = setup_test_db().await;
+
+            let specialties = create_specialty_list_concurrent(&db, 8, 4).await.unwrap();
+
+            let mut ids: Vec<_> = specialties.iter().map(|s| s.id).collect();
+            ids.sort_unstable();
+            ids.dedup();
+            assert_eq!(ids.len(), 8);
+            assert_eq!(count_specialties(&db).await.unwrap(), 8);
+        }
+
+        #[tokio::test]
+        async fn test_create_list_concurrent_with_lazy_fields() {
+            let db = setup_test_db().await;
+
+            let doctors = create_doctor_list_concurrent(&db, 5, 2).await.unwrap();
+
+            assert_eq!(doctors.len(), 5);
+            assert_eq!(count_doctors(&db).await.unwrap(), 5);
+            assert_eq!(count_specialties(&db).await.unwrap(), 5);
+        }
+
+        #[tokio::test]
+        async fn test_create_list_concurrent_returns_error() {
+            let db = setup_test_db().await;
+            let stmt = sea_orm::Statement::from_string(
+                db.get_database_backend(),
+                "DROP TABLE specialties",
+            );
+            db.execute(stmt).await.unwrap();
+
+            assert!(create_specialty_list_concurrent(&db, 3, 2).await.is_err());
+        }
+    }
+
+    mod preview_tests {
+        use super::*;
+
+        #[test]
+        fn test_preview_specialty() {
+            let specialty = create_specialty_builder()
+                .name("Preview".to_string())
+                .preview();
+
+            assert_eq!(specialty.id, 0);
+            assert_eq!(specialty.name, "Preview");
+            assert!(specialty.is_active);
+        }
+
+        #[test]
+        fn test_preview_keeps_builder_usable() {
+            let builder = create_doctor_builder().specialty_id(5);
+
+            let doctor = builder.preview();
+            assert_eq!(doctor.id, 0);
+            assert_eq!(doctor.specialty_id, 5);
+
+            let active = builder.build();
+            assert_eq!(active.email, ActiveValue::Set(doctor.email));
+        }
+
+        #[test]
+        #[should_panic(expected = "lazy field 'specialty_id' must be set when using build()")]
+        fn test_preview_requires_lazy_fields() {
+            create_doctor_builder().preview();
+        }
+    }
+
+    mod shared_parents_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_list_sharing_parents() {
+            let db = setup_test_db().await;
+
+            let doctors = create_doctor_list_sharing_parents(&db, 10).await.unwrap();
+
+            assert_eq!(doctors.len(), 10);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+            assert!(
+                doctors
+                    .iter()
+                    .all(|d| d.specialty_id == doctors[0].specialty_id)
+            );
+            assert_eq!(count_doctors(&db).await.unwrap(), 10);
+        }
+
+        #[tokio::test]
+        async fn test_list_sharing_parents_empty() {
+            let db = setup_test_db().await;
+
+            let doctors = create_doctor_list_sharing_parents(&db, 0).await.unwrap();
+
+            assert!(doctors.is_empty());
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+        }
+    }
+
+    mod array_column_tests {
+        use super::*;
+
+        #[test]
+        fn test_vec_field_on_builder_and_build() {
+            let builder = create_profile_builder();
+            assert_eq!(builder.diff_from_defaults(), Vec::<&str>::new());
+
+            let tags = vec!["cardio".to_string(), "senior".to_string()];
+            let builder = builder.tags(tags.clone());
+            assert_eq!(builder.diff_from_defaults(), ["tags"]);
+
+            let active = builder.clone().build();
+            assert_eq!(active.tags, ActiveValue::Set(tags.clone()));
+            assert_eq!(builder.preview().tags, tags);
+            assert_eq!(
+                create_profile_builder().build().tags,
+                ActiveValue::Set(vec!["general".to_string()])
+            );
+        }
+
+        /// Requer um Postgres em `LOCO_FACTORY_POSTGRES_URL`
+        #[tokio::test]
+        #[ignore = "requires Postgres: set LOCO_FACTORY_POSTGRES_URL and run with --ignored"]
+        async fn test_vec_field_round_trips_on_postgres() {
+            let url = std::env::var("LOCO_FACTORY_POSTGRES_URL")
+                .expect("LOCO_FACTORY_POSTGRES_URL must point to a Postgres database");
+            let db = Database::connect(&url).await.unwrap();
+            let backend = db.get_database_backend();
+            let schema = Schema::new(backend);
+            let drop = sea_orm::sea_query::Table::drop()
+                .table(profiles::Entity)
+                .if_exists()
+                .to_owned();
+            db.execute(backend.build(&drop)).await.unwrap();
+            db.execute(backend.build(&schema.create_table_from_entity(profiles::Entity)))
+                .await
+                .unwrap();
+
+            let tags = vec!["cardio".to_string(), "senior".to_string()];
+            let profile = create_profile_builder()
+                .tags(tags.clone())
+                .create(&db)
+                .await
+                .unwrap();
+            let stored = profiles::Entity::find_by_id(profile.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(stored.tags, tags);
+            assert_eq!(create_profile(&db).await.unwrap().tags, ["general"]);
+
+            db.execute(backend.build(&drop)).await.unwrap();
+        }
+    }
+
+    mod assert_created_tests {
+        use super::*;
+        use crate::assert_created;
+
+        #[tokio::test]
+        async fn test_assert_created_returns_model() {
+            let db = setup_test_db().await;
+
+            let specialty = assert_created!(create_specialty(&db));
+            let doctor = assert_created!(
+                create_doctor_builder()
+                    .specialty_id(specialty.id)
+                    .create(&db)
+            );
+
+            assert_eq!(specialty.name, "Test Specialty");
+            assert_eq!(doctor.specialty_id, specialty.id);
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "factory `create_specialty` failed to create a record")]
+        async fn test_assert_created_names_the_factory() {
+            let db = setup_test_db().await;
+            let stmt = sea_orm::Statement::from_string(
+                db.get_database_backend(),
+                "DROP TABLE specialties",
+            );
+            db.execute(stmt).await.unwrap();
+
+            assert_created!(create_specialty(&db));
+        }
+    }
+
+    mod into_setter_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_setters_accept_into() {
+            let db = setup_test_db().await;
+
+            let specialty = create_specialty_builder()
+                .name("Cardiology")
+                .description("Heart".to_string())
+                .create(&db)
+                .await
+                .unwrap();
+            assert_eq!(specialty.name, "Cardiology");
+            assert_eq!(specialty.description.as_deref(), Some("Heart"));
+
+            let mut builder = create_specialty_builder();
+            builder.set_name("Neurology").set_description(None);
+            let specialty = builder.create(&db).await.unwrap();
+            assert_eq!(specialty.name, "Neurology");
+            assert_eq!(specialty.description, None);
+        }
+    }
+
+    mod route_tests {
+        use super::*;
+        use crate::DbRouter;
+
+        async fn count_accounts(db: &DatabaseConnection) -> u64 {
+            accounts::Entity::find().count(db).await.unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_route_by_region() {
+            let router = DbRouter::new(vec![setup_test_db().await, setup_test_db().await]);
+
+            let us = create_account_routed(&router).await.unwrap();
+            let eu = create_account_builder()
+                .region("eu")
+                .create_routed(&router)
+                .await
+                .unwrap();
+
+            assert_eq!(us.region, "us");
+            assert_eq!(eu.region, "eu");
+            assert_eq!(count_accounts(&router[0]).await, 1);
+            assert_eq!(count_accounts(&router[1]).await, 1);
+
+            let stored = accounts::Entity::find_by_id(eu.id)
+                .one(&router[1])
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.region, "eu");
+            assert_eq!(router.connections().len(), 2);
+        }
+    }
+
+    #[cfg(feature = "summary")]
+    mod summary_tests {
+        use super::*;
+        use crate::assert_created;
+
+        #[tokio::test]
+        async fn test_summarize_lists_id_and_label() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty_builder()
+                .name("Cardiology")
+                .create(&db)
+                .await
+                .unwrap();
+
+            let summary = summarize_specialty(&specialty);
+            assert!(summary.contains(&format!("id: {}", specialty.id)));
+            assert!(summary.contains("name: \"Cardiology\""));
+            assert_eq!(
+                summarize_doctor(&create_doctor(&db).await.unwrap()),
+                "doctor { id: 1 }"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_assert_created_checks_with_summary() {
+            let db = setup_test_db().await;
+            let specialty =
+                assert_created!(create_specialty(&db), summarize_specialty, |specialty| {
+                    specialty.is_active
+                });
+            assert!(specialty.is_active);
+        }
+
+        #[tokio::test]
+        #[should_panic(
+            expected = "record created by `create_specialty` failed the check: specialty { id: 1"
+        )]
+        async fn test_assert_created_panics_with_summary() {
+            let db = setup_test_db().await;
+            assert_created!(create_specialty(&db), summarize_specialty, |specialty| {
+                !specialty.is_active
+            });
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cuja specialty vem de uma closure inline
+        inline_lazy_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "Ines".to_string(),
+                last_name: String = "Lima".to_string(),
+                email: String = format!("inline_{}@example.com", Uuid::new_v4()),
+                license_number: String = format!("INL{}", Uuid::new_v4().simple()),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = |db| async move {
+                    create_specialty_builder()
+                        .name("Inline")
+                        .create(db)
+                        .await
+                        .map(|specialty| specialty.id)
+                },
+            }
+        }
+    }
+
+    mod inline_lazy_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_lazy_default_inline_closure() {
+            let db = setup_test_db().await;
+            let doctor = create_inline_lazy_doctor(&db).await.unwrap();
+
+            let specialty = find_specialty_by_id(&db, doctor.specialty_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(specialty.name, "Inline");
+        }
+    }
+
+    mod placeholder_tests {
+        use super::*;
+
+        #[test]
+        fn test_build_placeholder_fills_unset_lazy_field() {
+            let model = create_doctor_builder()
+                .first_name("Ana")
+                .build_placeholder();
+
+            assert_eq!(model.specialty_id, ActiveValue::Set(0));
+            assert_eq!(model.first_name, ActiveValue::Set("Ana".to_string()));
+        }
+
+        #[test]
+        fn test_build_placeholder_keeps_set_and_unset_lazy_fields() {
+            let model = create_doctor_builder().specialty_id(7).build_placeholder();
+            assert_eq!(model.specialty_id, ActiveValue::Set(7));
+
+            let model = create_doctor_builder()
+                .unset_specialty_id()
+                .build_placeholder();
+            assert_eq!(model.specialty_id, sea_orm::ActiveValue::NotSet);
+        }
+    }
+
+    mod setter_doc_tests {
+        #[test]
+        fn test_setter_doc_names_the_field() {
+            assert_eq!(
+                define_factory!(@setter_doc is_active, "Define o valor de";),
+                "Define o valor de `is_active`"
+            );
+            assert_eq!(
+                define_factory!(@setter_doc is_active, "Define o valor de", " sem consumir o builder";),
+                "Define o valor de `is_active` sem consumir o builder"
+            );
+        }
+
+        #[test]
+        fn test_setter_doc_uses_the_field_docs() {
+            assert_eq!(
+                define_factory!(@setter_doc name, "Define o valor de"; " Nome exibido" " da specialty"),
+                " Nome exibido\n da specialty\n"
+            );
+        }
+    }
+
+    #[cfg(feature = "time")]
+    mod time_tests {
+        use super::*;
+
+        pub mod events {
+            use sea_orm::entity::prelude::*;
+
+            #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+            #[sea_orm(table_name = "events")]
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+                pub name: String,
+                pub starts_at: TimeDateTimeWithTimeZone,
+                pub deleted_at: Option<TimeDateTimeWithTimeZone>,
+            }
+
+            #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+            pub enum Relation {}
+
+            impl ActiveModelBehavior for ActiveModel {}
+        }
+
+        fn starts_at() -> time::OffsetDateTime {
+            time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap()
+        }
+
+        define_factory! {
+            event => events::Model {
+                active_model: events::ActiveModel,
+                fields: {
+                    name: String = "Launch".to_string(),
+                    starts_at: time::OffsetDateTime = starts_at(),
+                },
+                soft_delete: deleted_at
+            }
+        }
+
+        async fn setup_events_db() -> DatabaseConnection {
+            let db = setup_test_db().await;
+            let schema = Schema::new(db.get_database_backend());
+            let stmt = schema.create_table_from_entity(events::Entity);
+            db.execute(db.get_database_backend().build(&stmt))
+                .await
+                .expect("Failed to create events table");
+            db
+        }
+
+        #[tokio::test]
+        async fn test_time_offset_date_time_round_trips() {
+            let db = setup_events_db().await;
+            let event = create_event(&db).await.unwrap();
+
+            let stored = events::Entity::find_by_id(event.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.starts_at, starts_at());
+            assert_eq!(stored.deleted_at, None);
+        }
+
+        #[tokio::test]
+        async fn test_time_soft_delete_column() {
+            let db = setup_events_db().await;
+            let event = create_event_deleted(&db).await.unwrap();
+
+            assert!(event.deleted_at.unwrap() <= time::OffsetDateTime::now_utc());
+        }
+    }
+
+    mod create_schema_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_schema_sets_up_tables() {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            let db = create_schema!(db; specialties::Entity, doctors::Entity, patients::Entity)
+                .await
+                .unwrap();
+
+            create_specialty(&db).await.unwrap();
+            create_doctor(&db).await.unwrap();
+            create_patient(&db).await.unwrap();
+
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+            assert_eq!(doctors::Entity::find().count(&db).await.unwrap(), 1);
+            assert_eq!(patients::Entity::find().count(&db).await.unwrap(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_create_schema_reports_errors() {
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+            let result = create_schema!(db; specialties::Entity, specialties::Entity).await;
+
+            assert!(result.is_err());
+        }
+    }
+
+    mod lazy_with_tests {
+        use super::*;
+        use sea_orm::{QueryOrder, QuerySelect};
+
+        async fn newest_specialty_id(db: &DatabaseConnection) -> Result<i32, DbErr> {
+            specialties::Entity::find()
+                .order_by_desc(specialties::Column::Id)
+                .limit(1)
+                .one(db)
+                .await?
+                .map(|specialty| specialty.id)
+                .ok_or_else(|| DbErr::RecordNotFound("no specialty".to_string()))
+        }
+
+        #[tokio::test]
+        async fn test_set_lazy_field_with_async_closure() {
+            let db = setup_test_db().await;
+            create_specialty_list(&db, 3).await.unwrap();
+            let newest = newest_specialty_id(&db).await.unwrap();
+
+            let doctor = create_doctor_builder()
+                .set_specialty_id_with(|db| async move { newest_specialty_id(&db).await })
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.specialty_id, newest);
+            assert_eq!(count_specialties(&db).await.unwrap(), 3);
+        }
+
+        #[tokio::test]
+        async fn test_plain_setter_overrides_closure() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let builder = create_doctor_builder().set_specialty_id_with(|_db| async {
+                Err(DbErr::Custom("not called".to_string()))
+            });
+            assert!(builder.requires_db());
+            assert_eq!(builder.diff_from_defaults(), vec!["specialty_id"]);
+
+            let doctor = builder
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
+            assert_eq!(doctor.specialty_id, specialty.id);
+        }
+    }
+
+    mod create_as_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_as_maps_the_model() {
+            let db = setup_test_db().await;
+            let (id, name) = create_specialty_builder()
+                .name("Oncology")
+                .create_as(&db, |specialty| (specialty.id, specialty.name))
+                .await
+                .unwrap();
+
+            assert_eq!(name, "Oncology");
+            assert_eq!(
+                find_specialty_by_id(&db, id).await.unwrap().unwrap().name,
+                "Oncology"
+            );
+        }
+    }
+
+    mod assert_count_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_assert_doctor_count() {
+            let db = setup_test_db().await;
+            create_doctor_list(&db, 2).await.unwrap();
+
+            assert_doctor_count(&db, 2).await;
+            assert_specialty_count(&db, 2).await;
+        }
+
+        #[tokio::test]
+        #[should_panic(expected = "table `doctors` has 1 rows, expected 2")]
+        async fn test_assert_doctor_count_names_the_table() {
+            let db = setup_test_db().await;
+            create_doctor(&db).await.unwrap();
+
+            assert_doctor_count(&db, 2).await;
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty listando o `id` em `fields` por engano
+        listed_id_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                id: i32 = 42,
+                name: String = "Listed Id".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    mod primary_key_not_set_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_listed_primary_key_is_not_set() {
+            let db = setup_test_db().await;
+            let first = create_listed_id_specialty(&db).await.unwrap();
+            let second = create_listed_id_specialty_builder()
+                .id(7)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(first.id, 1);
+            assert_eq!(second.id, 2);
+            assert_eq!(
+                create_listed_id_specialty_builder().build().id,
+                ActiveValue::NotSet
+            );
+        }
+
+        #[tokio::test]
+        async fn test_explicit_primary_key_is_set() {
+            let db = setup_test_db().await;
+            let setting = create_setting_builder().id(500).create(&db).await.unwrap();
+
+            assert_eq!(setting.id, 500);
+        }
+    }
+
+    mod tags_tests {
+        use super::*;
+        use crate::DynFactory;
+        use crate::dyn_factory::factories_with_tag;
+
+        #[test]
+        fn test_factories_with_tag() {
+            let registry: Vec<Box<dyn DynFactory>> = vec![
+                Box::new(SpecialtyFactory),
+                Box::new(DoctorFactory),
+                Box::new(PatientFactory),
+            ];
+
+            assert_eq!(factories_with_tag(&registry, "core"), vec!["specialty"]);
+            assert_eq!(
+                factories_with_tag(&registry, "demo"),
+                vec!["specialty", "doctor"]
+            );
+            assert!(factories_with_tag(&registry, "missing").is_empty());
+        }
+
+        #[test]
+        fn test_tags_constant() {
+            assert_eq!(SpecialtyFactory::TAGS, &["core", "demo"]);
+            assert!(PatientFactory::TAGS.is_empty());
+            assert_eq!(DoctorFactory.name(), "doctor");
+        }
+    }
+
+    #[cfg(feature = "loco")]
+    mod loco_tests {
+        use super::*;
+
+        use loco_rs::app::{AppContext, SharedStore};
+        use loco_rs::cache::{self, Cache};
+        use loco_rs::config::Config;
+        use loco_rs::environment::Environment;
+        use loco_rs::storage::{self, Storage};
+        use std::sync::Arc;
+
+        async fn app_context() -> AppContext {
+            let config: Config = serde_json::from_value(serde_json::json!({
+                "logger": { "enable": false, "level": "off", "format": "compact" },
+                "server": { "binding": "localhost", "port": 5150, "host": "http://localhost" },
+                "database": {
+                    "uri": "sqlite::memory:",
+                    "enable_logging": false,
+                    "min_connections": 1,
+                    "max_connections": 1,
+                    "connect_timeout": 500,
+                    "idle_timeout": 500,
+                },
+            }))
+            .unwrap();
+            AppContext {
+                environment: Environment::Test,
+                db: setup_test_db().await,
+                queue_provider: None,
+                config,
+                mailer: None,
+                storage: Arc::new(Storage::single(storage::drivers::mem::new())),
+                cache: Arc::new(Cache::new(cache::drivers::null::new())),
+                shared_store: Arc::new(SharedStore::default()),
+            }
+        }
+
+        #[tokio::test]
+        async fn test_create_through_app_context() {
+            let ctx = app_context().await;
+
+            let specialty = create_specialty_ctx(&ctx).await.unwrap();
+            let doctor = create_doctor_ctx(&ctx).await.unwrap();
+
+            assert_eq!(
+                find_specialty_by_id(&ctx.db, specialty.id).await.unwrap(),
+                Some(specialty)
+            );
+            assert_eq!(doctor.id, 1);
+        }
+    }
+
+    mod spread_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_spread_keeps_manual_fields() {
+            let db = setup_test_db().await;
+            let shared = SpecialtyParams {
+                is_active: false,
+                ..Default::default()
+            };
+
+            let specialty = create_specialty_builder()
+                .name("Manual")
+                .spread(&shared)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Manual");
+            assert!(!specialty.is_active);
+
+            let builder = create_specialty_builder().name("Manual").spread(&shared);
+            assert_eq!(builder.diff_from_defaults(), vec!["name", "is_active"]);
+        }
+
+        #[test]
+        fn test_spread_copies_changed_fields_and_lazies() {
+            let shared = DoctorParams {
+                first_name: "Shared".to_string(),
+                specialty_id: Some(9),
+                ..Default::default()
+            };
+
+            let model = create_doctor_builder()
+                .last_name("Manual")
+                .spread(&shared)
+                .build();
+
+            assert_eq!(model.first_name, ActiveValue::Set("Shared".to_string()));
+            assert_eq!(model.last_name, ActiveValue::Set("Manual".to_string()));
+            assert_eq!(model.specialty_id, ActiveValue::Set(9));
+        }
+    }
+
+    define_factory! {
+        /// Cria um badge cujo campo `code` é a coluna `badge_code`
+        badge => badges::Model {
+            active_model: badges::ActiveModel,
+            fields: {
+                code: String = format!("BDG-{}", Uuid::new_v4().simple()),
+            },
+            unique: [code]
+        }
+    }
+
+    mod column_name_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_finder_on_renamed_column() {
+            let db = setup_test_db().await;
+            let badge = create_badge_builder()
+                .code("GOLD")
+                .create(&db)
+                .await
+                .unwrap();
+            create_badge(&db).await.unwrap();
+
+            assert_eq!(find_badge_by_code(&db, "GOLD").await.unwrap(), Some(badge));
+            assert_eq!(find_badge_by_code(&db, "SILVER").await.unwrap(), None);
+            assert_eq!(
+                sea_orm::IdenStatic::as_str(&badge_column_of("code").unwrap()),
+                "badge_code"
+            );
+        }
+    }
+
+    mod validate_tests {
+        use super::*;
+        use crate::FieldError;
+
+        #[test]
+        fn test_validate_then_build_collects_all_errors() {
+            let errors = create_specialty_builder()
+                .name("")
+                .description(None)
+                .validate_then_build()
+                .unwrap_err();
+
+            assert_eq!(
+                errors,
+                vec![
+                    FieldError {
+                        field: "name",
+                        message: "must not be empty".to_string(),
+                    },
+                    FieldError {
+                        field: "description",
+                        message: "is required".to_string(),
+                    },
+                ]
+            );
+            assert_eq!(errors[1].to_string(), "description: is required");
+        }
+
+        #[test]
+        fn test_validate_then_build_returns_model() {
+            let model = create_specialty_builder()
+                .name("Cardiology")
+                .validate_then_build()
+                .unwrap();
+
+            assert_eq!(model.name, ActiveValue::Set("Cardiology".to_string()));
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor reaproveitando uma specialty existente
+        shared_specialty_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "Sofia".to_string(),
+                last_name: String = "Reis".to_string(),
+                email: String = format!("shared_{}@example.com", Uuid::new_v4()),
+                license_number: String = format!("SHR{}", Uuid::new_v4().simple()),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+            },
+            relations: { specialty_id: specialty(reuse_existing) }
+        }
+    }
+
+    mod reuse_existing_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_reuse_existing_parent() {
+            let db = setup_test_db().await;
+            let first = create_shared_specialty_doctor(&db).await.unwrap();
+            let second = create_shared_specialty_doctor(&db).await.unwrap();
+
+            assert_eq!(first.specialty_id, second.specialty_id);
+            assert_specialty_count(&db, 1).await;
+        }
+
+        #[tokio::test]
+        async fn test_reuse_existing_skips_configured_parent() {
+            let db = setup_test_db().await;
+            let shared = create_shared_specialty_doctor(&db).await.unwrap();
+
+            let customized = create_shared_specialty_doctor_builder()
+                .with_specialty(|s| s.name("Oncology"))
+                .create(&db)
+                .await
+                .unwrap();
+            let explicit = create_shared_specialty_doctor_builder()
+                .specialty_id(shared.specialty_id)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_ne!(customized.specialty_id, shared.specialty_id);
+            assert_eq!(explicit.specialty_id, shared.specialty_id);
+            assert_specialty_count(&db, 2).await;
+        }
+
+        #[tokio::test]
+        async fn test_always_create_parent() {
+            let db = setup_test_db().await;
+            let first = create_doctor(&db).await.unwrap();
+            let second = create_doctor(&db).await.unwrap();
+
+            assert_ne!(first.specialty_id, second.specialty_id);
+            assert_specialty_count(&db, 2).await;
+        }
+    }
+
+    mod id_and_model_tests {
         use super::*;
 
-        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-        #[sea_orm(table_name = "specialties")]
-        pub struct Model {
-            #[sea_orm(primary_key)]
-            pub id: i32,
-            pub uuid: Uuid,
-            pub name: String,
-            pub description: Option<String>,
-            pub is_active: bool,
+        #[tokio::test]
+        async fn test_create_id_and_model() {
+            let db = setup_test_db().await;
+            let (sid, spec) = create_specialty_id_and_model(&db).await.unwrap();
+
+            assert_eq!(sid, spec.id);
+            assert_eq!(find_specialty_by_id(&db, sid).await.unwrap(), Some(spec));
         }
+    }
 
-        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-        pub enum Relation {}
+    mod insert_tests {
+        use super::*;
 
-        impl ActiveModelBehavior for ActiveModel {}
+        #[tokio::test]
+        async fn test_insert_populates_primary_key() {
+            let db = setup_test_db().await;
+            let active = create_specialty_builder().name("Returning").build();
+
+            let inserted = crate::insert::insert(active, &db).await.unwrap();
+            assert_eq!(inserted.id, 1);
+            assert_eq!(inserted.name, "Returning");
+
+            let created = create_specialty(&db).await.unwrap();
+            assert_eq!(created.id, 2);
+        }
     }
 
-    pub mod doctors {
+    mod refresh_defaults_tests {
         use super::*;
 
-        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-        #[sea_orm(table_name = "doctors")]
-        pub struct Model {
-            #[sea_orm(primary_key)]
-            pub id: i32,
-            pub uuid: Uuid,
-            pub first_name: String,
-            pub last_name: String,
-            pub email: String,
-            pub specialty_id: i32,
-            pub license_number: String,
-            pub phone: Option<String>,
-            pub is_active: bool,
+        #[test]
+        fn test_refresh_defaults_keeps_overrides() {
+            let mut builder = create_specialty_builder().name("Kept");
+            let uuid = builder.clone().build().uuid.unwrap();
+
+            builder.refresh_defaults();
+            let model = builder.build();
+
+            assert_ne!(model.uuid.unwrap(), uuid);
+            assert_eq!(model.name, ActiveValue::Set("Kept".to_string()));
+        }
+    }
+
+    mod related_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_with_loaded_related() {
+            let db = setup_test_db().await;
+            let (doctor, specialty) = create_doctor_with_loaded_specialty(&db).await.unwrap();
+
+            assert_eq!(specialty.id, doctor.specialty_id);
+            assert_eq!(
+                find_specialty_by_id(&db, doctor.specialty_id)
+                    .await
+                    .unwrap(),
+                Some(specialty)
+            );
+        }
+
+        #[tokio::test]
+        async fn test_create_for_parent_model() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
+
+            let doctor = create_doctor_for(&db, &specialty).await.unwrap();
+            let built = crate::BelongsTo::belongs_to(create_doctor_builder(), &specialty).build();
+
+            assert_eq!(doctor.specialty_id, specialty.id);
+            assert_eq!(built.specialty_id, ActiveValue::Set(specialty.id));
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+        }
+    }
+
+    mod build_list_with_tests {
+        use super::*;
+
+        #[test]
+        fn test_build_list_with_index() {
+            let models = build_specialty_list_with(3, |i, b| b.name(format!("a-{i}")));
+
+            let names: Vec<_> = models.iter().map(|model| model.name.clone()).collect();
+            assert_eq!(
+                names,
+                vec![
+                    ActiveValue::Set("a-0".to_string()),
+                    ActiveValue::Set("a-1".to_string()),
+                    ActiveValue::Set("a-2".to_string()),
+                ]
+            );
+            assert_ne!(models[0].uuid, models[1].uuid);
+        }
+    }
+
+    #[cfg(feature = "log")]
+    mod or_log_tests {
+        use super::*;
+        use std::sync::Mutex;
+
+        static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        struct CapturingLogger;
+
+        impl log::Log for CapturingLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                if record.target() == "loco_factory" {
+                    RECORDS
+                        .lock()
+                        .unwrap()
+                        .push(format!("{} {}", record.level(), record.args()));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        #[tokio::test]
+        async fn test_create_or_log_logs_and_returns_none() {
+            log::set_logger(&CapturingLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Error);
+            let db = Database::connect("sqlite::memory:").await.unwrap();
+
+            assert_eq!(create_specialty_or_log(&db).await, None);
+
+            let records = RECORDS.lock().unwrap();
+            assert_eq!(records.len(), 1);
+            assert!(records[0].starts_with("ERROR factory `specialty` failed to create a record:"));
+            assert!(records[0].contains("no such table"));
+        }
+
+        #[tokio::test]
+        async fn test_create_or_log_returns_model() {
+            let db = setup_test_db().await;
+
+            assert!(create_specialty_or_log(&db).await.is_some());
+        }
+    }
+
+    define_factory! {
+        /// Cria uma note passando pelo `before_save` do model
+        note => notes::Model {
+            active_model: notes::ActiveModel,
+            fields: {
+                title: String = "First Note".to_string(),
+                slug: String = "unset".to_string(),
+            }
+        }
+    }
+
+    define_factory! {
+        /// Cria uma note com um INSERT direto, sem os hooks do model
+        raw_note => notes::Model {
+            active_model: notes::ActiveModel,
+            fields: {
+                title: String = "First Note".to_string(),
+                slug: String = "unset".to_string(),
+            },
+            run_model_hooks: false
+        }
+    }
+
+    mod model_hooks_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_model_hooks_run_after_factory_values() {
+            let db = setup_test_db().await;
+            let note = create_note_builder()
+                .title("Hello World")
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(note.slug, "hello-world");
+        }
+
+        #[tokio::test]
+        async fn test_model_hooks_can_be_skipped() {
+            let db = setup_test_db().await;
+            let note = create_raw_note(&db).await.unwrap();
+
+            assert_eq!(note.slug, "unset");
+            assert_eq!(note.id, 1);
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty cujo id é um newtype gerado pela macro
+        newtype_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Newtype Specialty".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            id_newtype: NewtypeSpecialtyId
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor cuja specialty vem de um id newtype
+        newtype_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "Nina".to_string(),
+                last_name: String = "Prado".to_string(),
+                email: String = format!("newtype_{}@example.com", Uuid::new_v4()),
+                license_number: String = format!("NWT{}", Uuid::new_v4().simple()),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_newtype_specialty_id,
+            }
+        }
+    }
+
+    mod id_newtype_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_id_newtype_wires_into_foreign_key() {
+            let db = setup_test_db().await;
+            let specialty_id: NewtypeSpecialtyId = get_newtype_specialty_id(&db).await.unwrap();
+            let doctor = create_newtype_doctor_builder()
+                .specialty_id(specialty_id.clone())
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(doctor.specialty_id, specialty_id.0);
+            assert_eq!(i32::from(specialty_id), doctor.specialty_id);
+        }
+
+        #[tokio::test]
+        async fn test_id_newtype_lazy_default() {
+            let db = setup_test_db().await;
+            let doctor = create_newtype_doctor(&db).await.unwrap();
+
+            let specialty = find_specialty_by_id(&db, doctor.specialty_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(specialty.name, "Newtype Specialty");
+        }
+    }
+
+    define_factory! {
+        /// Cria um dossier com metadata JSON
+        dossier => dossiers::Model {
+            active_model: dossiers::ActiveModel,
+            fields: {
+                metadata: serde_json::Value = serde_json::json!({ "verified": false }),
+            }
+        }
+    }
+
+    mod json_tests {
+        use super::*;
+        use serde_json::json;
+
+        #[tokio::test]
+        async fn test_json_default_round_trips() {
+            let db = setup_test_db().await;
+            let dossier = create_dossier(&db).await.unwrap();
+
+            assert_eq!(dossier.metadata, json!({ "verified": false }));
+        }
+
+        #[tokio::test]
+        async fn test_json_override_round_trips() {
+            let db = setup_test_db().await;
+            let metadata = json!({ "verified": true, "tags": ["cardio", "night"] });
+            let builder = create_dossier_builder().metadata(metadata.clone());
+            assert_eq!(builder.diff_from_defaults(), vec!["metadata"]);
+
+            let dossier = builder.create(&db).await.unwrap();
+            let stored = dossiers::Entity::find_by_id(dossier.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.metadata, metadata);
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty numerada pela sequência da factory
+        sequenced_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = format!("Specialty {}", sequenced_specialty_sequence_next()),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    mod sequence_tests {
+        use super::*;
+        use crate::clear_all_sequences;
+
+        #[tokio::test]
+        async fn test_sequence_scope_restarts_numbering() {
+            let db = setup_test_db().await;
+            let _outer = sequenced_specialty_sequence_scope();
+            let first = create_sequenced_specialty(&db).await.unwrap();
+            create_sequenced_specialty(&db).await.unwrap();
+            assert_eq!(first.name, "Specialty 1");
+
+            {
+                let _inner = sequenced_specialty_sequence_scope();
+                let inner = create_sequenced_specialty(&db).await.unwrap();
+                assert_eq!(inner.name, "Specialty 1");
+            }
+
+            let after = create_sequenced_specialty(&db).await.unwrap();
+            assert_eq!(after.name, "Specialty 1");
+        }
+
+        #[test]
+        fn test_reset_sequence() {
+            sequenced_specialty_sequence_next();
+            reset_sequenced_specialty_sequence();
+
+            assert_eq!(sequenced_specialty_sequence_next(), 1);
+            assert_eq!(sequenced_specialty_sequence_next(), 2);
+        }
+
+        #[test]
+        fn test_clear_all_sequences() {
+            sequenced_specialty_sequence_next();
+            sequenced_specialty_sequence_next();
+            ticket_sequence_next();
+
+            clear_all_sequences();
+
+            assert_eq!(sequenced_specialty_sequence_next(), 1);
+            assert_eq!(ticket_sequence_next(), 1);
+        }
+    }
+
+    mod nullable_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_with_null_field() {
+            let db = setup_test_db().await;
+            let specialty = create_specialty_with_null_description(&db).await.unwrap();
+
+            assert_eq!(specialty.description, None);
+            let stored = find_specialty_by_id(&db, specialty.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.description, None);
+        }
+    }
+
+    define_factory! {
+        /// Cria um token partindo de `ActiveModel::new()`
+        token => tokens::Model {
+            active_model: tokens::ActiveModel,
+            fields: {
+                label: String = "api".to_string(),
+            },
+            base: new
+        }
+    }
+
+    mod base_new_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_base_new_keeps_values_seeded_by_new() {
+            let db = setup_test_db().await;
+            let token = create_token(&db).await.unwrap();
+
+            assert_eq!(token.secret, "seeded-by-new");
+            assert_eq!(token.label, "api");
+            assert_eq!(
+                create_token_builder().build().secret,
+                ActiveValue::Set("seeded-by-new".to_string())
+            );
+        }
+    }
+
+    mod refetch_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_create_and_refetch_sees_trigger_values() {
+            let db = setup_test_db().await;
+            db.execute_unprepared(
+                "CREATE TRIGGER triage_ticket AFTER INSERT ON tickets \
+                 BEGIN UPDATE tickets SET status = 'triaged' WHERE id = NEW.id; END",
+            )
+            .await
+            .unwrap();
+
+            let ticket = create_ticket_builder()
+                .title("Flickering screen")
+                .create_and_refetch(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(ticket.title, "Flickering screen");
+            assert_eq!(ticket.status, "triaged");
+        }
+    }
+
+    define_factory! {
+        /// Cria uma specialty com o ActiveModel escolhido por `cfg`
+        cfg_specialty => specialties::Model {
+            active_model: {
+                #[cfg(test)] specialties::ActiveModel,
+                #[cfg(not(test))] backend_without::ActiveModel,
+            },
+            fields: {
+                name: String = "Cfg Specialty".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            }
+        }
+    }
+
+    mod cfg_active_model_tests {
+        use super::*;
+
+        #[tokio::test]
+        async fn test_active_model_selected_by_cfg() {
+            let db = setup_test_db().await;
+            let specialty = create_cfg_specialty(&db).await.unwrap();
+
+            assert_eq!(specialty.name, "Cfg Specialty");
+            let _: specialties::ActiveModel = create_cfg_specialty_builder().build();
         }
+    }
 
-        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-        pub enum Relation {
-            #[sea_orm(
-                belongs_to = "super::specialties::Entity",
-                from = "Column::SpecialtyId",
-                to = "super::specialties::Column::Id"
-            )]
-            Specialty,
+    mod field_default_override_tests {
+        use super::*;
+        use crate::with_field_default_override;
+
+        #[tokio::test]
+        async fn test_override_applies_inside_scope_only() {
+            let db = setup_test_db().await;
+
+            let inside = with_field_default_override("is_active", false, || async {
+                let specialty = create_specialty(&db).await.unwrap();
+                let built = create_specialty_builder().create(&db).await.unwrap();
+                (specialty, built)
+            })
+            .await;
+            let after = create_specialty(&db).await.unwrap();
+
+            assert!(!inside.0.is_active);
+            assert!(!inside.1.is_active);
+            assert!(after.is_active);
         }
 
-        impl Related<super::specialties::Entity> for Entity {
-            fn to() -> RelationDef {
-                Relation::Specialty.def()
-            }
+        #[tokio::test]
+        async fn test_override_ignores_fields_of_another_type() {
+            let db = setup_test_db().await;
+            let _guard = crate::FieldDefaultOverride::new("is_active", "no");
+
+            assert!(create_specialty(&db).await.unwrap().is_active);
         }
+    }
 
-        impl ActiveModelBehavior for ActiveModel {}
+    define_factory! {
+        /// Cria um comment de uma associação polimórfica
+        comment => comments::Model {
+            active_model: comments::ActiveModel,
+            fields: {
+                body: String = "Nice work".to_string(),
+                commentable_type: String = "Specialty".to_string(),
+                commentable_id: i32 = 1,
+            },
+            polymorphic: { commentable: (commentable_type, commentable_id) }
+        }
     }
 
-    pub mod patients {
+    mod polymorphic_tests {
         use super::*;
 
-        #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
-        #[sea_orm(table_name = "patients")]
-        pub struct Model {
-            #[sea_orm(primary_key)]
-            pub id: i32,
-            pub uuid: Uuid,
-            pub first_name: String,
-            pub last_name: String,
-            pub email: String,
-            pub date_of_birth: chrono::NaiveDate,
-            pub phone: String,
-            pub address: Option<String>,
+        #[tokio::test]
+        async fn test_polymorphic_setter_sets_both_columns() {
+            let db = setup_test_db().await;
+            let comment = create_comment_builder()
+                .for_commentable("Doctor", 5)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(comment.commentable_type, "Doctor");
+            assert_eq!(comment.commentable_id, 5);
         }
+    }
 
-        #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-        pub enum Relation {}
+    mod batch_tests {
+        use super::*;
+        use crate::FactoryBatch;
 
-        impl ActiveModelBehavior for ActiveModel {}
-    }
+        #[tokio::test]
+        async fn test_batch_inserts_heterogeneous_factories() {
+            let db = setup_test_db().await;
+            let mut batch = FactoryBatch::new();
+            let specialty = batch.push(create_specialty_builder().name("Batch Specialty"));
+            let doctor = batch.push(create_doctor_builder());
 
-    /// Setup de banco em memória SQLite
-    async fn setup_test_db() -> DatabaseConnection {
-        let db = Database::connect("sqlite::memory:")
-            .await
-            .expect("Failed to connect to test database");
+            let created = batch.commit(&db).await.unwrap();
 
-        let schema = Schema::new(sea_orm::DatabaseBackend::Sqlite);
+            assert_eq!(created.get(&specialty).name, "Batch Specialty");
+            assert_eq!(
+                created.get(&doctor).specialty_id,
+                created.get(&specialty).id
+            );
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
+            assert_eq!(count_doctors(&db).await.unwrap(), 1);
+        }
 
-        let stmt = schema.create_table_from_entity(specialties::Entity);
-        db.execute(db.get_database_backend().build(&stmt))
-            .await
-            .expect("Failed to create specialties table");
+        #[tokio::test]
+        async fn test_batch_creates_pushed_parent_first() {
+            let db = setup_test_db().await;
+            let existing = create_specialty(&db).await.unwrap();
+            let mut batch = FactoryBatch::new();
+            let doctor = batch.push(create_doctor_builder());
+            let specialty = batch.push(create_specialty_builder());
+            let other = batch.push(create_doctor_builder().specialty_id(existing.id));
 
-        let stmt = schema.create_table_from_entity(doctors::Entity);
-        db.execute(db.get_database_backend().build(&stmt))
-            .await
-            .expect("Failed to create doctors table");
+            let created = batch.commit(&db).await.unwrap();
 
-        let stmt = schema.create_table_from_entity(patients::Entity);
-        db.execute(db.get_database_backend().build(&stmt))
-            .await
-            .expect("Failed to create patients table");
+            assert_eq!(
+                created.get(&doctor).specialty_id,
+                created.get(&specialty).id
+            );
+            assert_eq!(created.get(&other).specialty_id, existing.id);
+            assert_eq!(count_specialties(&db).await.unwrap(), 2);
+        }
 
-        db
-    }
+        #[tokio::test]
+        async fn test_batch_rolls_back_lazy_parents() {
+            let db = setup_test_db().await;
+            let mut batch = FactoryBatch::new();
+            batch.push(create_doctor_builder());
+            batch.push(create_setting_builder().id(7));
+            batch.push(create_setting_builder().id(7));
 
-    async fn count_specialties(db: &DatabaseConnection) -> Result<u64, DbErr> {
-        use sea_orm::EntityTrait;
-        specialties::Entity::find().count(db).await
-    }
+            assert!(batch.commit(&db).await.is_err());
 
-    async fn count_doctors(db: &DatabaseConnection) -> Result<u64, DbErr> {
-        use sea_orm::EntityTrait;
-        doctors::Entity::find().count(db).await
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+            assert_eq!(count_doctors(&db).await.unwrap(), 0);
+        }
+
+        #[tokio::test]
+        async fn test_batch_is_atomic() {
+            let db = setup_test_db().await;
+            let mut batch = FactoryBatch::new();
+            batch.push(create_specialty_builder());
+            batch.push(create_setting_builder().id(7));
+            batch.push(create_setting_builder().id(7));
+
+            assert!(batch.commit(&db).await.is_err());
+
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
+            assert_eq!(settings::Entity::find().count(&db).await.unwrap(), 0);
+        }
     }
 
-    async fn count_patients(db: &DatabaseConnection) -> Result<u64, DbErr> {
-        use sea_orm::EntityTrait;
-        patients::Entity::find().count(db).await
+    thread_local! {
+        // Quantidade de inserts que ainda devem falhar em `insert_flaky_specialty`
+        static FLAKY_FAILURES: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
     }
 
-    async fn find_specialty_by_id(
-        db: &DatabaseConnection,
-        id: i32,
-    ) -> Result<Option<specialties::Model>, DbErr> {
-        use sea_orm::EntityTrait;
-        specialties::Entity::find_by_id(id).one(db).await
+    async fn insert_flaky_specialty<C: sea_orm::ConnectionTrait>(
+        model: specialties::ActiveModel,
+        db: &C,
+    ) -> Result<specialties::Model, DbErr> {
+        if FLAKY_FAILURES.with(|failures| failures.replace(failures.get().saturating_sub(1))) > 0 {
+            return Err(DbErr::Custom("deadlock detected".to_string()));
+        }
+        model.insert(db).await
     }
 
     define_factory! {
-        /// Cria uma specialty de teste
-        specialty => specialties::Model {
+        /// Cria uma specialty cujo insert falha nas primeiras tentativas
+        flaky_specialty => specialties::Model {
             active_model: specialties::ActiveModel,
             fields: {
-                name: String = "Test Specialty".to_string(),
-                description: Option<String> = Some("Test Description".to_string()),
+                name: String = "Flaky Specialty".to_string(),
                 uuid: Uuid = Uuid::new_v4(),
                 is_active: bool = true,
-            }
+            },
+            insert_with: insert_flaky_specialty
         }
     }
 
-    // async fn get_specialty_id(db: &DatabaseConnection) -> Result<i32, DbErr> {
-    //    create_specialty(db).await.map(|s| s.id)
-    // }
+    mod retry_policy_tests {
+        use super::*;
+        use std::time::Duration;
 
-    define_factory! {
-        /// Cria um doctor de teste
-        doctor => doctors::Model {
-            active_model: doctors::ActiveModel,
-            fields: {
-                first_name: String = "John".to_string(),
-                last_name: String = "Doe".to_string(),
-                email: String = format!("doctor_{}@example.com", Uuid::new_v4()),
-                license_number: String = format!("LIC{}", Uuid::new_v4().to_string()[..8].to_uppercase()),
-                uuid: Uuid = Uuid::new_v4(),
-                phone: Option<String> = Some("+5511999999999".to_string()),
-                is_active: bool = true,
-            },
-            lazy_fields: {
-                specialty_id: i32 = get_specialty_id,
-            }
+        #[tokio::test]
+        async fn test_with_retry_policy_succeeds_after_one_failure() {
+            let db = setup_test_db().await;
+            FLAKY_FAILURES.with(|failures| failures.set(1));
+            let policy = crate::RetryPolicy::new(1, Duration::ZERO)
+                .retry_if(|err| err.to_string().contains("deadlock"));
+
+            let specialty = create_flaky_specialty_builder()
+                .with_retry_policy(policy)
+                .create(&db)
+                .await
+                .unwrap();
+
+            assert_eq!(specialty.name, "Flaky Specialty");
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
         }
-    }
 
-    define_factory! {
-        /// Cria um patient de teste
-        patient => patients::Model {
-            active_model: patients::ActiveModel,
-            fields: {
-                first_name: String = "Maria".to_string(),
-                last_name: String = "Silva".to_string(),
-                email: String = format!("patient_{}@example.com", Uuid::new_v4()),
-                date_of_birth: chrono::NaiveDate = chrono::NaiveDate::from_ymd_opt(1990, 1, 1).unwrap(),
-                phone: String = "+5511988888888".to_string(),
-                uuid: Uuid = Uuid::new_v4(),
-                address: Option<String> = Some("Rua Teste, 123".to_string()),
-            }
+        #[tokio::test]
+        async fn test_with_retry_skips_non_transient_errors() {
+            let db = setup_test_db().await;
+            FLAKY_FAILURES.with(|failures| failures.set(1));
+
+            let result = create_flaky_specialty_builder()
+                .with_retry(3, Duration::ZERO)
+                .create(&db)
+                .await;
+
+            assert!(result.is_err());
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
         }
     }
 
-    mod specialties_factory_tests {
+    mod eq_ignoring_tests {
         use super::*;
 
         #[tokio::test]
-        async fn test_create_specialty_with_defaults() {
+        async fn test_assert_eq_ignoring_refetched_model() {
             let db = setup_test_db().await;
-            let specialty = create_specialty(&db).await.unwrap();
+            let created = create_specialty(&db).await.unwrap();
+            let fetched = find_specialty_by_id(&db, created.id)
+                .await
+                .unwrap()
+                .unwrap();
+            let built = specialties::Model {
+                id: 0,
+                ..fetched.clone()
+            };
 
-            assert_eq!(specialty.name, "Test Specialty");
-            assert_eq!(specialty.description, Some("Test Description".to_string()));
-            assert!(specialty.id > 0);
-            assert!(specialty.is_active);
+            assert_specialty_eq_ignoring(&created, &fetched, &[]);
+            assert_specialty_eq_ignoring(&built, &fetched, &["id"]);
         }
 
-        #[tokio::test]
-        async fn test_create_multiple_specialties() {
-            let db = setup_test_db().await;
-
-            let specialty1 = create_specialty(&db).await.unwrap();
-            let specialty2 = create_specialty(&db).await.unwrap();
-            let specialty3 = create_specialty(&db).await.unwrap();
+        #[test]
+        #[should_panic(
+            expected = "`name`: String(Some(\"Other\")) != String(Some(\"Test Specialty\"))"
+        )]
+        fn test_assert_eq_ignoring_reports_differences() {
+            let left = specialties::Model {
+                id: 1,
+                name: "Other".to_string(),
+                description: None,
+                uuid: Uuid::nil(),
+                is_active: true,
+            };
+            let right = specialties::Model {
+                id: 2,
+                name: "Test Specialty".to_string(),
+                ..left.clone()
+            };
 
-            assert_ne!(specialty1.id, specialty2.id);
-            assert_ne!(specialty2.id, specialty3.id);
-            assert_ne!(specialty1.uuid, specialty2.uuid);
+            assert_specialty_eq_ignoring(&left, &right, &["id"]);
+        }
+    }
 
-            let count = count_specialties(&db).await.unwrap();
-            assert_eq!(count, 3);
+    define_factory! {
+        /// Cria uma invoice com valor monetário decimal
+        invoice => invoices::Model {
+            active_model: invoices::ActiveModel,
+            fields: {
+                amount: Decimal = Decimal::new(1000, 2),
+            }
         }
+    }
+
+    mod decimal_tests {
+        use super::*;
 
         #[tokio::test]
-        async fn test_specialty_uuid_is_unique() {
+        async fn test_decimal_amount_round_trips_exactly() {
             let db = setup_test_db().await;
+            let amount = Decimal::new(1, 1) + Decimal::new(2, 1);
+            let invoice = create_invoice_builder()
+                .amount(amount)
+                .create(&db)
+                .await
+                .unwrap();
 
-            let specialty1 = create_specialty(&db).await.unwrap();
-            let specialty2 = create_specialty(&db).await.unwrap();
+            let stored = invoices::Entity::find_by_id(invoice.id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(stored.amount, Decimal::new(3, 1));
+            assert_eq!(stored.amount.to_string(), "0.3");
+        }
 
-            assert_ne!(specialty1.uuid, specialty2.uuid);
+        #[test]
+        fn test_decimal_default_builds() {
+            assert_eq!(
+                create_invoice_builder().build().amount,
+                ActiveValue::Set(Decimal::new(1000, 2))
+            );
+        }
+    }
+
+    define_factory! {
+        /// Cria um doctor que só cria a specialty quando está ativo
+        guarded_doctor => doctors::Model {
+            active_model: doctors::ActiveModel,
+            fields: {
+                first_name: String = "John".to_string(),
+                last_name: String = "Doe".to_string(),
+                email: String = format!("doctor_{}@example.com", Uuid::new_v4()),
+                uuid: Uuid = Uuid::new_v4(),
+                license_number: String = "LIC-123".to_string(),
+                phone: Option<String> = None,
+                is_active: bool = true,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+            },
+            lazy_when: { specialty_id: |doctor| *doctor.is_active.as_ref() => 0 }
         }
+    }
+
+    mod lazy_when_tests {
+        use super::*;
 
         #[tokio::test]
-        async fn test_auto_increment_id() {
+        async fn test_lazy_default_runs_only_when_condition_holds() {
             let db = setup_test_db().await;
+            // O sentinela 0 não referencia uma specialty existente
+            db.execute_unprepared("PRAGMA foreign_keys = OFF")
+                .await
+                .unwrap();
 
-            let specialty1 = create_specialty(&db).await.unwrap();
-            let specialty2 = create_specialty(&db).await.unwrap();
-            let specialty3 = create_specialty(&db).await.unwrap();
+            let inactive = create_guarded_doctor_builder()
+                .is_active(false)
+                .create(&db)
+                .await
+                .unwrap();
+            assert_eq!(inactive.specialty_id, 0);
+            assert_eq!(count_specialties(&db).await.unwrap(), 0);
 
-            assert_eq!(specialty1.id + 1, specialty2.id);
-            assert_eq!(specialty2.id + 1, specialty3.id);
+            let active = create_guarded_doctor(&db).await.unwrap();
+            assert!(
+                find_specialty_by_id(&db, active.specialty_id)
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
         }
 
         #[tokio::test]
-        async fn test_specialty_persisted_correctly() {
+        async fn test_lazy_when_keeps_explicit_value() {
             let db = setup_test_db().await;
+            let specialty = create_specialty(&db).await.unwrap();
 
-            let created = create_specialty(&db).await.unwrap();
-            let found = find_specialty_by_id(&db, created.id).await.unwrap();
+            let doctor = create_guarded_doctor_builder()
+                .is_active(false)
+                .specialty_id(specialty.id)
+                .create(&db)
+                .await
+                .unwrap();
 
-            assert!(found.is_some());
-            let found = found.unwrap();
-            assert_eq!(found.id, created.id);
-            assert_eq!(found.name, created.name);
-            assert_eq!(found.uuid, created.uuid);
+            assert_eq!(doctor.specialty_id, specialty.id);
         }
     }
 
-    // ============================================
-    // TESTES - BUILDER PATTERN
-    // ============================================
+    define_factory! {
+        /// Cria uma specialty através do primary de um wrapper de conexões
+        replicated_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "Replicated Specialty".to_string(),
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            conn_accessor: true
+        }
+    }
 
-    mod builder_factory_tests {
+    mod conn_accessor_tests {
         use super::*;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct ReplicatedDb {
+            primary: DatabaseConnection,
+            replica: DatabaseConnection,
+            primary_calls: AtomicUsize,
+        }
+
+        impl crate::PrimaryConn for ReplicatedDb {
+            fn primary(&self) -> &DatabaseConnection {
+                self.primary_calls.fetch_add(1, Ordering::SeqCst);
+                &self.primary
+            }
+        }
+
         #[tokio::test]
-        async fn test_builder_with_default_values() {
-            let db = setup_test_db().await;
-            let specialty = CreateSpecialtyBuilder::new().create(&db).await.unwrap();
+        async fn test_inserts_go_through_primary() {
+            let conns = ReplicatedDb {
+                primary: setup_test_db().await,
+                replica: setup_test_db().await,
+                primary_calls: AtomicUsize::new(0),
+            };
 
-            assert_eq!(specialty.name, "Test Specialty");
-            assert_eq!(specialty.description, Some("Test Description".to_string()));
+            create_replicated_specialty(&conns).await.unwrap();
+            create_replicated_specialty_list(&conns, 2).await.unwrap();
+
+            assert!(conns.primary_calls.load(Ordering::SeqCst) > 0);
+            assert_eq!(count_specialties(&conns.primary).await.unwrap(), 3);
+            assert_eq!(count_specialties(&conns.replica).await.unwrap(), 0);
+            create_replicated_specialty(&conns.primary).await.unwrap();
         }
+    }
+
+    mod preflight_unique_tests {
+        use super::*;
+        use crate::FactoryError;
 
         #[tokio::test]
-        async fn test_builder_with_custom_name() {
+        async fn test_try_create_reports_duplicate_field() {
             let db = setup_test_db().await;
-
-            let specialty = CreateSpecialtyBuilder::new()
-                .name("Cardiology".to_string())
+            create_doctor_builder()
+                .email("house@example.com")
                 .create(&db)
                 .await
                 .unwrap();
 
-            assert_eq!(specialty.name, "Cardiology");
+            let result = create_doctor_builder()
+                .email("house@example.com")
+                .try_create(&db)
+                .await;
+
+            assert!(matches!(
+                result,
+                Err(FactoryError::Duplicate { field: "email" })
+            ));
+            assert_eq!(count_doctors(&db).await.unwrap(), 1);
         }
 
         #[tokio::test]
-        async fn test_builder_with_multiple_overrides() {
+        async fn test_try_create_inserts_unique_values() {
             let db = setup_test_db().await;
 
-            let custom_uuid = Uuid::new_v4();
-            let specialty = CreateSpecialtyBuilder::new()
-                .name("Neurology".to_string())
-                .description(Some("Brain specialist".to_string()))
-                .uuid(custom_uuid)
-                .is_active(false)
-                .create(&db)
-                .await
-                .unwrap();
+            let doctor = create_doctor_builder().try_create(&db).await.unwrap();
 
-            assert_eq!(specialty.name, "Neurology");
-            assert_eq!(specialty.description, Some("Brain specialist".to_string()));
-            assert_eq!(specialty.uuid, custom_uuid);
-            assert!(!specialty.is_active);
+            assert_eq!(count_doctors(&db).await.unwrap(), 1);
+            assert!(doctor.email.ends_with("@example.com"));
         }
 
         #[tokio::test]
-        async fn test_builder_with_none_description() {
+        async fn test_try_create_checks_transformed_value() {
             let db = setup_test_db().await;
-
-            let specialty = CreateSpecialtyBuilder::new()
-                .name("Surgery".to_string())
-                .description(None)
+            create_normalized_doctor_builder()
+                .email("house@example.com")
                 .create(&db)
                 .await
                 .unwrap();
 
-            assert_eq!(specialty.name, "Surgery");
-            assert_eq!(specialty.description, None);
+            let result = create_normalized_doctor_builder()
+                .email("House@Example.com")
+                .try_create(&db)
+                .await;
+
+            assert!(matches!(
+                result,
+                Err(FactoryError::Duplicate { field: "email" })
+            ));
+            assert_eq!(count_doctors(&db).await.unwrap(), 1);
+        }
+    }
+
+    define_factory! {
+        /// Cria um referral de uma specialty, opcionalmente ligado a um patient
+        referral => referrals::Model {
+            active_model: referrals::ActiveModel,
+            fields: {
+                reason: String = "Follow-up".to_string(),
+                patient_id: Option<i32> = None,
+            },
+            lazy_fields: {
+                specialty_id: i32 = get_specialty_id,
+            },
+            relations: { specialty_id: specialty, patient_id: patient }
         }
+    }
+
+    mod relations_tests {
+        use super::*;
 
         #[tokio::test]
-        async fn test_builder_helper_function() {
+        async fn test_create_with_relations_customizes_each_parent() {
             let db = setup_test_db().await;
 
-            let specialty = create_specialty_builder()
-                .name("Oncology".to_string())
-                .create(&db)
+            let referral = create_referral_builder()
+                .reason("Chest pain")
+                .create_with_relations(&db, |r| {
+                    r.specialty(|s| s.name("Cardiology"))
+                        .patient(|p| p.first_name("Ana"))
+                })
                 .await
                 .unwrap();
 
-            assert_eq!(specialty.name, "Oncology");
+            let specialty = find_specialty_by_id(&db, referral.specialty_id)
+                .await
+                .unwrap()
+                .unwrap();
+            let patient = patients::Entity::find_by_id(referral.patient_id.unwrap())
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(referral.reason, "Chest pain");
+            assert_eq!(specialty.name, "Cardiology");
+            assert_eq!(patient.first_name, "Ana");
         }
 
         #[tokio::test]
-        async fn test_builder_reuse() {
+        async fn test_unconfigured_relations_keep_defaults() {
             let db = setup_test_db().await;
 
-            let base_builder = CreateSpecialtyBuilder::new().name("Base Specialty".to_string());
-
-            let specialty1 = base_builder.clone().create(&db).await.unwrap();
-            let specialty2 = base_builder.create(&db).await.unwrap();
+            let referral = create_referral_with_relations(&db, |r| r).await.unwrap();
 
-            assert_eq!(specialty1.name, "Base Specialty");
-            assert_eq!(specialty2.name, "Base Specialty");
-            assert_ne!(specialty1.id, specialty2.id);
+            assert_eq!(referral.patient_id, None);
+            assert_eq!(count_specialties(&db).await.unwrap(), 1);
         }
+    }
 
-        #[test]
-        fn test_build_returns_active_model() {
-            let active_model = CreateSpecialtyBuilder::new()
-                .name("Test".to_string())
-                .build();
-
-            assert!(matches!(active_model.name, ActiveValue::Set(_)));
-            if let ActiveValue::Set(name) = active_model.name {
-                assert_eq!(name, "Test");
-            }
-        }
+    mod build_changes_tests {
+        use super::*;
 
         #[test]
-        fn test_build_does_not_require_database() {
-            let active_model = CreateSpecialtyBuilder::new()
-                .name("No DB Test".to_string())
-                .description(Some("Works without database".to_string()))
-                .build();
+        fn test_build_changes_only_sets_overridden_fields() {
+            let changes = create_specialty_builder().name("Renamed").build_changes();
 
-            if let ActiveValue::Set(name) = active_model.name {
-                assert_eq!(name, "No DB Test");
-            }
+            assert_eq!(changes.name, ActiveValue::Set("Renamed".to_string()));
+            assert_eq!(changes.id, ActiveValue::NotSet);
+            assert_eq!(changes.uuid, ActiveValue::NotSet);
+            assert_eq!(changes.is_active, ActiveValue::NotSet);
+            assert_eq!(changes.description, ActiveValue::NotSet);
         }
+    }
 
-        #[test]
-        fn test_build_with_custom_uuid() {
-            let custom_uuid = Uuid::new_v4();
-            let active_model = CreateSpecialtyBuilder::new().uuid(custom_uuid).build();
-
-            if let ActiveValue::Set(uuid) = active_model.uuid {
-                assert_eq!(uuid, custom_uuid);
+    define_factory! {
+        /// Cria um departamento de teste
+        department => departments::Model {
+            active_model: departments::ActiveModel,
+            fields: {
+                name: String = "General".to_string(),
             }
         }
+    }
 
-        #[test]
-        fn test_build_all_fields_set() {
-            let active_model = CreateSpecialtyBuilder::new()
-                .name("Complete".to_string())
-                .description(Some("Full description".to_string()))
-                .uuid(Uuid::new_v4())
-                .is_active(false)
-                .build();
+    define_factory! {
+        /// Cria uma ala de um departamento
+        ward => wards::Model {
+            active_model: wards::ActiveModel,
+            fields: {
+                name: String = "Ward A".to_string(),
+            },
+            lazy_fields: {
+                department_id: i32 = |db| async move {
+                    create_department(db).await.map(|department| department.id)
+                },
+            },
+            relations: { department_id: department }
+        }
+    }
 
-            assert!(matches!(active_model.name, ActiveValue::Set(_)));
-            assert!(matches!(active_model.description, ActiveValue::Set(_)));
-            assert!(matches!(active_model.uuid, ActiveValue::Set(_)));
-            assert!(matches!(active_model.is_active, ActiveValue::Set(_)));
+    define_factory! {
+        /// Cria um leito de uma ala
+        bed => beds::Model {
+            active_model: beds::ActiveModel,
+            fields: {
+                label: String = "Bed 1".to_string(),
+            },
+            lazy_fields: {
+                ward_id: i32 = |db| async move {
+                    create_ward(db).await.map(|ward| ward.id)
+                },
+            },
+            relations: { ward_id: ward }
         }
     }
 
-    mod patient_factory_tests {
+    mod grandparent_relations_tests {
         use super::*;
 
         #[tokio::test]
-        async fn test_create_patient_with_defaults() {
-            let db = setup_test_db().await;
-            let patient = create_patient(&db).await.unwrap();
-
-            assert_eq!(patient.first_name, "Maria");
-            assert_eq!(patient.last_name, "Silva");
-            assert!(patient.email.contains("@example.com"));
-            assert_eq!(patient.phone, "+5511988888888");
-        }
-
-        #[tokio::test]
-        async fn test_create_patient_with_custom_data() {
+        async fn test_with_parent_chains_three_levels() {
             let db = setup_test_db().await;
 
-            let patient = CreatePatientBuilder::new()
-                .first_name("Carlos".to_string())
-                .last_name("Santos".to_string())
-                .email("carlos@example.com".to_string())
-                .date_of_birth(chrono::NaiveDate::from_ymd_opt(1985, 5, 15).unwrap())
-                .phone("+5511977777777".to_string())
-                .address(Some("Av. Paulista, 1000".to_string()))
+            let bed = create_bed_builder()
+                .label("Bed 7")
+                .with_ward(|w| w.name("ICU").with_department(|d| d.name("Medicine")))
                 .create(&db)
                 .await
                 .unwrap();
 
-            assert_eq!(patient.first_name, "Carlos");
-            assert_eq!(patient.date_of_birth.year(), 1985);
-            assert_eq!(patient.address, Some("Av. Paulista, 1000".to_string()));
+            let ward = wards::Entity::find_by_id(bed.ward_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            let department = departments::Entity::find_by_id(ward.department_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(bed.label, "Bed 7");
+            assert_eq!(ward.name, "ICU");
+            assert_eq!(department.name, "Medicine");
+            assert_eq!(wards::Entity::find().count(&db).await.unwrap(), 1);
+            assert_eq!(departments::Entity::find().count(&db).await.unwrap(), 1);
         }
 
         #[tokio::test]
-        async fn test_create_multiple_patients() {
+        async fn test_create_with_relations_reaches_grandparent() {
             let db = setup_test_db().await;
 
-            for i in 0..5 {
-                let _ = CreatePatientBuilder::new()
-                    .first_name(format!("Patient{}", i))
-                    .create(&db)
-                    .await
-                    .unwrap();
-            }
+            let bed = create_bed_with_relations(&db, |r| {
+                r.ward(|w| w.with_department(|d| d.name("Surgery")))
+            })
+            .await
+            .unwrap();
 
-            let count = count_patients(&db).await.unwrap();
-            assert_eq!(count, 5);
+            let ward = wards::Entity::find_by_id(bed.ward_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            let department = departments::Entity::find_by_id(ward.department_id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(ward.name, "Ward A");
+            assert_eq!(department.name, "Surgery");
         }
     }
 
-    mod integration_tests {
+    define_factory! {
+        /// Cria um andar, cuja chave primária é `number`
+        floor => floors::Model {
+            active_model: floors::ActiveModel,
+            fields: {
+                label: String = "Ground".to_string(),
+            },
+            primary_key: { number: i32 }
+        }
+    }
+
+    define_factory! {
+        /// Cria uma sala de um andar
+        room => rooms::Model {
+            active_model: rooms::ActiveModel,
+            fields: {
+                name: String = "Room 1".to_string(),
+            },
+            lazy_fields: {
+                floor_number: i32 = get_floor_id,
+            },
+            belongs_to: { floor_number: floor },
+            relations: { floor_number: floor }
+        }
+    }
+
+    mod custom_key_relations_tests {
         use super::*;
 
         #[tokio::test]
-        async fn test_full_hospital_scenario() {
+        async fn test_with_parent_reads_parent_primary_key() {
             let db = setup_test_db().await;
+            create_floor(&db).await.unwrap();
 
-            let cardiology = create_specialty_builder()
-                .name("Cardiology".to_string())
-                .create(&db)
-                .await
-                .unwrap();
-
-            let neurology = create_specialty_builder()
-                .name("Neurology".to_string())
-                .create(&db)
-                .await
-                .unwrap();
-
-            let _doctor1 = create_doctor_builder()
-                .specialty_id(cardiology.id)
-                .first_name("Dr. House".to_string())
+            let room = create_room_builder()
+                .with_floor(|f| f.label("Second"))
                 .create(&db)
                 .await
                 .unwrap();
 
-            let _doctor2 = create_doctor_builder()
-                .specialty_id(neurology.id)
-                .first_name("Dr. Strange".to_string())
-                .create(&db)
+            let floor = floors::Entity::find_by_id(room.floor_number)
+                .one(&db)
                 .await
+                .unwrap()
                 .unwrap();
+            assert_eq!(floor.number, 2);
+            assert_eq!(floor.label, "Second");
+        }
 
-            let _patient1 = create_patient_builder()
-                .first_name("John".to_string())
+        #[tokio::test]
+        async fn test_belongs_to_reads_parent_primary_key() {
+            let db = setup_test_db().await;
+            create_floor(&db).await.unwrap();
+            let floor = create_floor_builder()
+                .label("Second")
                 .create(&db)
                 .await
                 .unwrap();
 
-            let _patient2 = create_patient_builder()
-                .first_name("Jane".to_string())
-                .create(&db)
-                .await
-                .unwrap();
+            let room = create_room_for(&db, &floor).await.unwrap();
+            let built = crate::BelongsTo::belongs_to(create_room_builder(), &floor).build();
 
-            assert_eq!(count_specialties(&db).await.unwrap(), 2);
-            assert_eq!(count_doctors(&db).await.unwrap(), 2);
-            assert_eq!(count_patients(&db).await.unwrap(), 2);
+            assert_eq!(floor.number, 2);
+            assert_eq!(room.floor_number, floor.number);
+            assert_eq!(built.floor_number, ActiveValue::Set(floor.number));
         }
     }
 
-    // ============================================
-    // TESTES - EDGE CASES
-    // ============================================
+    define_factory! {
+        /// Cria uma specialty com o nome do builder fixado
+        http_specialty => specialties::Model {
+            active_model: specialties::ActiveModel,
+            fields: {
+                name: String = "HTTP".to_string(),
+                description: Option<String> = None,
+                uuid: Uuid = Uuid::new_v4(),
+                is_active: bool = true,
+            },
+            builder_name: CreateHTTPSpecialtyBuilder
+        }
+    }
 
-    mod edge_case_tests {
+    mod builder_name_tests {
         use super::*;
 
         #[tokio::test]
-        async fn test_special_characters() {
+        async fn test_builder_constructed_by_custom_name() {
             let db = setup_test_db().await;
 
-            let specialty = CreateSpecialtyBuilder::new()
-                .name("Test 特殊 🏥".to_string())
-                .description(Some("With émojis".to_string()))
+            let specialty = CreateHTTPSpecialtyBuilder::new()
+                .name("HTTP/2")
                 .create(&db)
                 .await
                 .unwrap();
+            let builder: CreateHTTPSpecialtyBuilder = create_http_specialty_builder();
 
-            assert_eq!(specialty.name, "Test 特殊 🏥");
+            assert_eq!(specialty.name, "HTTP/2");
+            assert_eq!(builder.build().name, ActiveValue::Set("HTTP".to_string()));
+        }
+    }
+
+    /// Simula um crate externo de entidades, sem nada importado do módulo de testes
+    pub mod shared_entities {
+        pub mod departments {
+            use sea_orm::entity::prelude::*;
+
+            #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+            #[sea_orm(table_name = "departments")]
+            pub struct Model {
+                #[sea_orm(primary_key)]
+                pub id: i32,
+                pub name: String,
+            }
+
+            #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+            pub enum Relation {}
+
+            impl ActiveModelBehavior for ActiveModel {}
+        }
+    }
+
+    // Sem imports: a factory só enxerga as entidades pelo caminho completo
+    mod external_factories {
+        crate::define_factory! {
+            /// Cria um departamento do crate externo
+            external_department => crate::factory_tests::shared_entities::departments::Model {
+                active_model: crate::factory_tests::shared_entities::departments::ActiveModel,
+                fields: {
+                    name: String = format!("External {}", external_department_sequence_next()),
+                }
+            }
         }
+    }
+
+    mod external_entities_tests {
+        use super::external_factories::{
+            CreateExternalDepartmentBuilder, create_external_department,
+            create_external_department_list,
+        };
+        use super::*;
 
         #[tokio::test]
-        async fn test_uuid_uniqueness() {
+        async fn test_factory_for_entities_referenced_by_full_path() {
             let db = setup_test_db().await;
-            let mut uuids = std::collections::HashSet::new();
 
-            for _ in 0..50 {
-                let specialty = create_specialty(&db).await.unwrap();
-                assert!(uuids.insert(specialty.uuid));
-            }
+            let department = create_external_department(&db).await.unwrap();
+            let named = CreateExternalDepartmentBuilder::new()
+                .name("Radiology")
+                .create(&db)
+                .await
+                .unwrap();
+            create_external_department_list(&db, 2).await.unwrap();
 
-            assert_eq!(uuids.len(), 50);
+            assert!(department.name.starts_with("External "));
+            assert_eq!(named.name, "Radiology");
+            assert_eq!(
+                shared_entities::departments::Entity::find()
+                    .count(&db)
+                    .await
+                    .unwrap(),
+                4
+            );
         }
     }
 }
@@ -0,0 +1,16 @@
+//! Failure logging for the generated `create_$fn_name_or_log` helpers.
+
+use sea_orm::DbErr;
+
+/// Turns the result of a factory call into an `Option`, logging the error (at `error`
+/// level, through the `log` crate) instead of returning it.
+#[doc(hidden)]
+pub fn log_failure<T>(factory: &str, result: Result<T, DbErr>) -> Option<T> {
+    match result {
+        Ok(model) => Some(model),
+        Err(err) => {
+            log::error!(target: "loco_factory", "factory `{factory}` failed to create a record: {err}");
+            None
+        }
+    }
+}
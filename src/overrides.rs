@@ -0,0 +1,86 @@
+//! Temporary defaults for a field across every factory.
+//!
+//! The defaults generated by [`define_factory!`](crate::define_factory) consult this registry
+//! by field name before evaluating their own expression, so a test can, for instance, make
+//! every factory's `is_active` default to `false`:
+//!
+//! ```rust,ignore
+//! with_field_default_override("is_active", false, || async {
+//!     let specialty = create_specialty(&db).await.unwrap();
+//!     assert!(!specialty.is_active);
+//! })
+//! .await;
+//! ```
+//!
+//! An override only applies to fields of exactly the same type (`false` doesn't replace an
+//! `Option<bool>` default). Overrides are thread-local, like the tests driving them on a
+//! `#[tokio::test]` runtime; on a multi-threaded runtime, keep the factories on the task that
+//! registered the override.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+
+thread_local! {
+    static OVERRIDES: RefCell<HashMap<&'static str, Rc<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// Runs `f` with `value` as the default of every factory field named `field`, awaiting the
+/// future it returns. The previous override, if any, is restored afterwards.
+pub async fn with_field_default_override<T, F, Fut>(
+    field: &'static str,
+    value: T,
+    f: F,
+) -> Fut::Output
+where
+    T: Any,
+    F: FnOnce() -> Fut,
+    Fut: Future,
+{
+    let _guard = FieldDefaultOverride::new(field, value);
+    f().await
+}
+
+/// Overrides the default of every factory field named `field` until dropped.
+///
+/// The guard form of [`with_field_default_override`], for overrides covering the rest of a test.
+#[must_use = "the override is removed as soon as the guard is dropped"]
+pub struct FieldDefaultOverride {
+    field: &'static str,
+    previous: Option<Rc<dyn Any>>,
+}
+
+impl FieldDefaultOverride {
+    /// Registers `value` as the default of the fields named `field`.
+    pub fn new<T: Any>(field: &'static str, value: T) -> Self {
+        let previous =
+            OVERRIDES.with(|overrides| overrides.borrow_mut().insert(field, Rc::new(value)));
+        Self { field, previous }
+    }
+}
+
+impl Drop for FieldDefaultOverride {
+    fn drop(&mut self) {
+        OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            match self.previous.take() {
+                Some(previous) => overrides.insert(self.field, previous),
+                None => overrides.remove(self.field),
+            };
+        });
+    }
+}
+
+/// Returns the override registered for `field` when it has type `T`, else evaluates `default`.
+#[doc(hidden)]
+pub fn or_default<T: Any + Clone>(field: &'static str, default: impl FnOnce() -> T) -> T {
+    let value = OVERRIDES.with(|overrides| {
+        overrides
+            .borrow()
+            .get(field)
+            .and_then(|value| value.downcast_ref::<T>().cloned())
+    });
+    value.unwrap_or_else(default)
+}
@@ -0,0 +1,87 @@
+//! Classification of insert errors that a factory may retry.
+
+use std::future::Future;
+use std::time::Duration;
+
+use sea_orm::{DbErr, RuntimeErr, SqlErr};
+
+/// Returns `true` when `err` was caused by a unique constraint (or primary key) violation.
+///
+/// Used by factories declared with `retry_on_conflict` to decide whether regenerating the
+/// defaults and inserting again can succeed. Any other error is returned to the caller as is.
+pub fn is_unique_violation(err: &DbErr) -> bool {
+    matches!(err.sql_err(), Some(SqlErr::UniqueConstraintViolation(_)))
+}
+
+/// Returns `true` when `err` is a transient failure that may succeed if run again unchanged:
+/// a deadlock or serialization failure on Postgres (SQLSTATE `40P01` / `40001`), or a busy or
+/// locked database on SQLite (extended result codes included).
+pub fn is_transient(err: &DbErr) -> bool {
+    use sea_orm::sqlx::{self, postgres::PgDatabaseError, sqlite::SqliteError};
+
+    let (DbErr::Exec(RuntimeErr::SqlxError(sqlx::Error::Database(e)))
+    | DbErr::Query(RuntimeErr::SqlxError(sqlx::Error::Database(e)))) = err
+    else {
+        return false;
+    };
+    let Some(code) = e.code() else {
+        return false;
+    };
+    if e.try_downcast_ref::<PgDatabaseError>().is_some() {
+        matches!(&*code, "40001" | "40P01")
+    } else if e.try_downcast_ref::<SqliteError>().is_some() {
+        code.parse::<i32>()
+            .is_ok_and(|code| matches!(code & 0xff, 5 | 6))
+    } else {
+        false
+    }
+}
+
+/// How a builder retries its insert, set with `with_retry` or `with_retry_policy`.
+///
+/// ```rust,ignore
+/// let policy = RetryPolicy::new(3, Duration::from_millis(50)).retry_if(|err| my_check(err));
+/// create_specialty_builder().with_retry_policy(policy).create(&db).await?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    attempts: u32,
+    backoff: Duration,
+    classify: fn(&DbErr) -> bool,
+}
+
+impl RetryPolicy {
+    /// Retries up to `attempts` times on [`is_transient`] errors, waiting `backoff` before each
+    /// retry.
+    pub fn new(attempts: u32, backoff: Duration) -> Self {
+        Self {
+            attempts,
+            backoff,
+            classify: is_transient,
+        }
+    }
+
+    /// Replaces [`is_transient`] with `classify` to decide which errors are retried.
+    pub fn retry_if(mut self, classify: fn(&DbErr) -> bool) -> Self {
+        self.classify = classify;
+        self
+    }
+
+    /// Runs `f`, running it again while it fails with a retryable error and attempts remain.
+    pub async fn run<T, F, Fut>(&self, mut f: F) -> Result<T, DbErr>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DbErr>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match f().await {
+                Err(err) if attempt < self.attempts && (self.classify)(&err) => {
+                    attempt += 1;
+                    tokio::time::sleep(self.backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Routing of inserts between several connections, for factories with a `route` or
+//! `conn_accessor` clause.
+
+use std::ops::Index;
+
+use sea_orm::DatabaseConnection;
+
+/// The connections a routed factory can insert into, e.g. one per shard or region.
+///
+/// The `route` closure of the factory receives the router and returns one of its
+/// connections, usually by index (`&dbs[1]`).
+#[derive(Debug, Clone)]
+pub struct DbRouter {
+    connections: Vec<DatabaseConnection>,
+}
+
+impl DbRouter {
+    /// Creates a router over `connections`, indexed in the given order.
+    pub fn new(connections: Vec<DatabaseConnection>) -> Self {
+        Self { connections }
+    }
+
+    /// Returns every connection of the router.
+    pub fn connections(&self) -> &[DatabaseConnection] {
+        &self.connections
+    }
+}
+
+impl Index<usize> for DbRouter {
+    type Output = DatabaseConnection;
+
+    fn index(&self, index: usize) -> &DatabaseConnection {
+        &self.connections[index]
+    }
+}
+
+/// A connection wrapper whose writes must go to a primary connection, e.g. a struct holding
+/// a primary and a read replica.
+///
+/// Factories declared with `conn_accessor: true` take any implementor in `create_$fn_name` and
+/// `create_$fn_name_list`, and insert through [`primary`](PrimaryConn::primary):
+///
+/// ```rust,ignore
+/// impl PrimaryConn for AppDb {
+///     fn primary(&self) -> &DatabaseConnection {
+///         &self.primary
+///     }
+/// }
+///
+/// let specialty = create_specialty(&app_db).await?;
+/// ```
+pub trait PrimaryConn {
+    /// The connection inserts go through.
+    fn primary(&self) -> &DatabaseConnection;
+}
+
+impl PrimaryConn for DatabaseConnection {
+    fn primary(&self) -> &DatabaseConnection {
+        self
+    }
+}
+
+/// Calls a `|active_model, dbs| &dbs[...]` route closure.
+///
+/// Going through this function (instead of calling the closure directly) lets the compiler
+/// infer the closure's argument types and ties the returned connection to the router.
+#[doc(hidden)]
+pub fn route<'a, A, F>(partial: &A, router: &'a DbRouter, f: F) -> &'a DatabaseConnection
+where
+    F: FnOnce(&A, &'a DbRouter) -> &'a DatabaseConnection,
+{
+    f(partial, router)
+}
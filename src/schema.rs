@@ -0,0 +1,28 @@
+//! Test database setup from `sea-orm` entities.
+
+/// Creates the tables of the given entities on a connection, in order, and returns the
+/// connection.
+///
+/// Replaces one `Schema::create_table_from_entity` + `execute` block per table. The macro
+/// evaluates to a future of `Result<DatabaseConnection, DbErr>` that takes ownership of the
+/// connection; list parents before the entities referencing them:
+///
+/// ```rust,ignore
+/// let db = Database::connect("sqlite::memory:").await?;
+/// let db = create_schema!(db; specialties::Entity, doctors::Entity, patients::Entity).await?;
+/// ```
+#[macro_export]
+macro_rules! create_schema {
+    ($db:expr; $($entity:expr),+ $(,)?) => {
+        async move {
+            let db: sea_orm::DatabaseConnection = $db;
+            let backend = sea_orm::ConnectionTrait::get_database_backend(&db);
+            let schema = sea_orm::Schema::new(backend);
+            $(
+                let stmt = schema.create_table_from_entity($entity);
+                sea_orm::ConnectionTrait::execute(&db, backend.build(&stmt)).await?;
+            )+
+            Ok::<_, sea_orm::DbErr>(db)
+        }
+    };
+}
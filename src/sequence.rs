@@ -0,0 +1,66 @@
+//! Per-factory sequences for numbered default values.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    static REGISTRY: RefCell<BTreeMap<&'static str, fn()>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Records the reset function of a factory sequence, called by the generated
+/// `$fn_name_sequence_next` so [`clear_all_sequences`] can find it.
+#[doc(hidden)]
+pub fn register(factory: &'static str, reset: fn()) {
+    REGISTRY.with(|registry| {
+        registry.borrow_mut().entry(factory).or_insert(reset);
+    });
+}
+
+/// Resets every factory sequence used so far on the current thread, so each restarts at 1.
+///
+/// Meant for a shared test setup, instead of calling each `reset_$fn_name_sequence`:
+///
+/// ```rust,ignore
+/// clear_all_sequences();
+/// assert_eq!(specialty_sequence_next(), 1);
+/// ```
+///
+/// A sequence joins the registry the first time its `$fn_name_sequence_next` runs on the thread;
+/// one never advanced is already at its start.
+pub fn clear_all_sequences() {
+    let resets: Vec<fn()> = REGISTRY.with(|registry| registry.borrow().values().copied().collect());
+    for reset in resets {
+        reset();
+    }
+}
+
+/// Resets a factory sequence when created and again when dropped.
+///
+/// Returned by the generated `$fn_name_sequence_scope()`, so a test block gets numbering
+/// starting at 1 without manual reset calls:
+///
+/// ```rust,ignore
+/// let _scope = specialty_sequence_scope();
+/// assert_eq!(specialty_sequence_next(), 1);
+/// ```
+///
+/// Sequences are thread-local, like the tests driving them on a `#[tokio::test]` runtime, so
+/// tests running in parallel don't share numbering.
+#[must_use = "the sequence is reset again as soon as the scope is dropped"]
+pub struct SequenceScope {
+    reset: fn(),
+}
+
+impl SequenceScope {
+    /// Calls `reset` now and when the scope is dropped.
+    pub fn new(reset: fn()) -> Self {
+        reset();
+        Self { reset }
+    }
+}
+
+impl Drop for SequenceScope {
+    fn drop(&mut self) {
+        (self.reset)();
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers used by [`define_factory!`](crate::define_factory) to apply `transform` and `map` closures.
+
+/// Applies a `transform` closure to the final value of a field.
+///
+/// Going through this function (instead of calling the closure directly) lets the compiler
+/// infer the closure's argument type from the field value.
+#[doc(hidden)]
+pub fn apply<T, F>(value: T, f: F) -> T
+where
+    F: FnOnce(T) -> T,
+{
+    f(value)
+}
+
+/// Applies the `map` closure of the `returns` clause to an inserted model.
+///
+/// Same inference trick as [`apply`], for a closure changing the value's type.
+#[doc(hidden)]
+pub fn map<T, U, F>(value: T, f: F) -> U
+where
+    F: FnOnce(T) -> U,
+{
+    f(value)
+}
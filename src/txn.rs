@@ -0,0 +1,51 @@
+//! Transaction shared by the creates of a batch.
+//!
+//! Lazy defaults receive the `DatabaseConnection` (`async fn(&DatabaseConnection)`), so a
+//! transaction can't be handed to them. Instead, [`run`] opens a transaction and makes it the
+//! current one for the task while the closure runs: every factory `create` in it, including the
+//! parents created by lazy defaults (`get_specialty_id`), `with_$parent` and the `reuse_existing`
+//! strategy, writes through that transaction and is rolled back with it. Queries a lazy closure
+//! sends to the connection itself bypass the transaction.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use sea_orm::{DatabaseConnection, DatabaseTransaction, DbErr, TransactionTrait};
+
+tokio::task_local! {
+    static CURRENT: Arc<DatabaseTransaction>;
+}
+
+/// The transaction opened by the enclosing [`run`], if any.
+#[doc(hidden)]
+pub fn current() -> Option<Arc<DatabaseTransaction>> {
+    CURRENT.try_with(Arc::clone).ok()
+}
+
+/// Runs `f` inside a transaction, committed when it returns `Ok` and rolled back otherwise.
+///
+/// Nested calls open a savepoint in the enclosing transaction.
+#[doc(hidden)]
+pub async fn run<T, F, Fut>(db: &DatabaseConnection, f: F) -> Result<T, DbErr>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, DbErr>>,
+{
+    let txn = Arc::new(match current() {
+        Some(outer) => outer.begin().await?,
+        None => db.begin().await?,
+    });
+    let result = CURRENT.scope(Arc::clone(&txn), f()).await;
+    let txn = Arc::try_unwrap(txn)
+        .map_err(|_| DbErr::Custom("transaction still in use after the batch".to_string()))?;
+    match result {
+        Ok(value) => {
+            txn.commit().await?;
+            Ok(value)
+        }
+        Err(err) => {
+            txn.rollback().await?;
+            Err(err)
+        }
+    }
+}